@@ -1,6 +1,23 @@
 use crate::new_build::Visitor;
 use crate::{file_explorer::RealFileExplorer, old_build};
+
+/// When set, the bundle is minimized even if the build script doesn't ask
+/// for it. `submit` sets it to fit the judge's source size limit.
+pub const MINIMIZE_ENV: &str = "RUST_COMPETITIVE_HELPER_MINIMIZE";
+
+/// Cargo reruns the build script whenever this changes. The helper gives it
+/// a fresh value when `main/` holds another task, so switching back to a task
+/// that wasn't edited still regenerates the bundle.
+pub const TASK_ENV: &str = "RUST_COMPETITIVE_HELPER_TASK";
+
+fn minimize_requested(minimize: bool) -> bool {
+    println!("cargo:rerun-if-env-changed={}", MINIMIZE_ENV);
+    minimize || std::env::var_os(MINIMIZE_ENV).is_some()
+}
+
 pub fn build_several_libraries(libraries: &[String], minimize: bool) {
+    println!("cargo:rerun-if-env-changed={}", TASK_ENV);
+    let minimize = minimize_requested(minimize);
     let file_explorer = RealFileExplorer::new();
     let code = old_build::build_several_libraries_impl(libraries, &file_explorer, minimize);
 
@@ -13,6 +30,8 @@ pub fn build() {
 }
 
 pub fn build_new(minimize: bool) {
+    println!("cargo:rerun-if-env-changed={}", TASK_ENV);
+    let minimize = minimize_requested(minimize);
     let mut visitor = Visitor::new(minimize, RealFileExplorer::new());
     visitor.build();
 }
@@ -74,11 +74,10 @@ pub struct FakeFileExplorer {
 
 impl FileExplorer for FakeFileExplorer {
     fn read_file(&self, filename: &str) -> Result<Vec<String>, String> {
-        Ok(self
-            .files
+        self.files
             .get(filename)
-            .unwrap_or_else(|| panic!("Can't open file: {}", &filename))
-            .clone())
+            .cloned()
+            .ok_or_else(|| format!("Can't open file: {}", &filename))
     }
 
     fn get_all_rs_files(&self, path_prefix: &str) -> Vec<String> {
@@ -33,10 +33,76 @@ pub struct IOType {
     pub pattern: Option<String>,
 }
 
+/// Where a test came from.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TestSource {
+    Sample,
+    Manual,
+    Stress,
+    Imported,
+}
+
+impl TestSource {
+    pub const fn tag(&self) -> &'static str {
+        match self {
+            TestSource::Sample => "sample",
+            TestSource::Manual => "manual",
+            TestSource::Stress => "stress",
+            TestSource::Imported => "imported",
+        }
+    }
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Test {
+    #[serde(default)]
     pub input: String,
+    #[serde(default)]
     pub output: String,
+    /// Name of the test's files in `tests/`; the 1-based position when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<TestSource>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default = "enabled_by_default", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+    /// Verdict the test is meant to get, e.g. `TL` for a test a naive
+    /// solution shouldn't pass in time.
+    #[serde(
+        rename = "expectedVerdict",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expected_verdict: Option<String>,
+}
+
+impl Test {
+    pub fn new(source: TestSource) -> Self {
+        Self {
+            input: String::new(),
+            output: String::new(),
+            name: None,
+            source: Some(source),
+            tags: Vec::new(),
+            enabled: true,
+            expected_verdict: None,
+        }
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.source.is_some_and(|source| source.tag() == tag) || self.tags.iter().any(|t| t == tag)
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -65,6 +131,18 @@ impl TestType {
     }
 }
 
+/// A subtask of an olympiad-style task: it scores its points only when all
+/// of its tests and all the groups it depends on pass.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TestGroup {
+    pub name: String,
+    pub points: u64,
+    /// Test names, or numeric ranges such as `3-7`.
+    pub tests: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[allow(non_snake_case)]
 pub struct Task {
@@ -74,7 +152,16 @@ pub struct Task {
     pub interactive: bool,
     #[serde(rename = "timeLimit")]
     pub time_limit: u64,
+    /// Memory limit in megabytes, as sent by Competitive Companion.
+    #[serde(
+        rename = "memoryLimit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub memory_limit: Option<u64>,
     pub tests: Vec<Test>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<TestGroup>,
     #[serde(rename = "testType")]
     pub test_type: TestType,
     pub input: IOType,
@@ -159,3 +246,10 @@ pub fn load_task<P: AsRef<Path>>(dir: P) -> Option<Task> {
     let stripped = first.trim().strip_prefix("//")?;
     serde_json::from_str::<Task>(stripped.trim()).ok()
 }
+
+/// Write the task config to `<dir>/task.json`.
+pub fn save_task<P: AsRef<Path>>(dir: P, task: &Task) -> Result<(), String> {
+    let path = dir.as_ref().join("task.json");
+    fs::write(&path, serde_json::to_string_pretty(task).unwrap())
+        .map_err(|err| format!("Can't write {}: {}", path.display(), err))
+}
@@ -399,11 +399,11 @@ impl<FE: FileExplorer> Visitor<FE> {
                 vec![library.to_string()],
                 l.tree.as_mut(),
             ) {
-                l.tree = Box::new(UseTree::Path(UsePath {
+                *l.tree = UseTree::Path(UsePath {
                     ident: Ident::new(&library, l.ident.span()),
                     colon2_token: l.colon2_token,
                     tree: l.tree.clone(),
-                }));
+                });
                 false
             } else {
                 self.in_root
@@ -8,6 +8,7 @@ use std::collections::BTreeMap;
 use std::fs;
 use std::fs::{read_dir, remove_dir_all, rename};
 use std::iter::once;
+use std::path::Path;
 
 fn contest_name(group: &str) -> String {
     match group.find('-') {
@@ -72,6 +73,14 @@ pub fn ask_archive(task_name: String, selection: usize) {
             format!("{}/{}.rs", path, task_name),
             main.clone(),
         );
+        let submissions = format!("tasks/{}/submissions.json", task_name);
+        if Path::new(&submissions).exists() {
+            fs::copy(
+                &submissions,
+                format!("{}/{}.submissions.json", path, task_name),
+            )
+            .unwrap();
+        }
         for file in find_additional_solution_files(&task_name) {
             let content = rust_competitive_helper_util::read_lines(format!(
                 "tasks/{}/src/{}",
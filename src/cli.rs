@@ -1,15 +1,25 @@
-use crate::{archiver, submit, task_creator};
-use rust_competitive_helper_util::{IOEnum, IOType, Task, Test, TestType};
+use crate::runner::{InputSource, RunOptions, TestOptions};
+use crate::submit::SubmitOptions;
+use crate::{archiver, runner, submit, task_creator};
+use rust_competitive_helper_util::{IOEnum, IOType, Task, Test, TestSource, TestType};
 use std::path::Path;
 
 pub fn run(args: &[String]) {
     let (cmd, rest) = (args[0].as_str(), &args[1..]);
     match cmd {
-        "submit" => {
-            if let Err(e) = expect_no_args(rest, "submit") {
+        "submit" => match parse_submit(rest) {
+            Ok(options) => submit::submit(&options),
+            Err(e) => fail(&format!("submit: {}\n\n{}", e, SUBMIT_USAGE)),
+        },
+        "verify" => {
+            if let Err(e) = expect_no_args(rest, "verify") {
                 fail(&e);
             }
-            submit::submit();
+            match runner::verify() {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => fail(&e),
+            }
         }
         "new" => match parse_new(rest) {
             Ok(args) => task_creator::create(build_task(args)),
@@ -19,6 +29,55 @@ pub fn run(args: &[String]) {
             Ok(args) => run_archive(args),
             Err(e) => fail(&format!("archive: {}\n\n{}", e, ARCHIVE_USAGE)),
         },
+        "test" => match parse_test(rest) {
+            Ok((name, options)) => match runner::run_tests(&name, &options) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => fail(&e),
+            },
+            Err(e) => fail(&format!("test: {}\n\n{}", e, TEST_USAGE)),
+        },
+        "watch" => match parse_task_name(rest, "watch") {
+            Ok(name) => {
+                if let Err(e) = runner::watch(&name) {
+                    fail(&e);
+                }
+            }
+            Err(e) => fail(&format!("watch: {}\n\n{}", e, WATCH_USAGE)),
+        },
+        "interact" => match parse_interact(rest) {
+            Ok((name, save)) => {
+                if let Err(e) = runner::interact(&name, save) {
+                    fail(&e);
+                }
+            }
+            Err(e) => fail(&format!("interact: {}\n\n{}", e, INTERACT_USAGE)),
+        },
+        "run" => match parse_run(rest) {
+            Ok((name, options)) => {
+                if let Err(e) = runner::run(&name, &options) {
+                    fail(&e);
+                }
+            }
+            Err(e) => fail(&format!("run: {}\n\n{}", e, RUN_USAGE)),
+        },
+        "calibrate" => match parse_calibrate(rest) {
+            Ok(Calibrate::Source) => print!("{}", runner::BENCHMARK),
+            Ok(Calibrate::Run { site, judge_ms }) => {
+                if let Err(e) = runner::calibrate(&site, judge_ms) {
+                    fail(&e);
+                }
+            }
+            Err(e) => fail(&format!("calibrate: {}\n\n{}", e, CALIBRATE_USAGE)),
+        },
+        "status" => match parse_status(rest) {
+            Ok(name) => {
+                if let Err(e) = submit::status(name.as_deref()) {
+                    fail(&e);
+                }
+            }
+            Err(e) => fail(&format!("status: {}\n\n{}", e, STATUS_USAGE)),
+        },
         "help" | "-h" | "--help" => println!("{}", HELP),
         other => fail(&format!("Unknown command: {}\n\n{}", other, HELP)),
     }
@@ -37,6 +96,188 @@ fn expect_no_args(args: &[String], cmd: &str) -> Result<(), String> {
     }
 }
 
+fn parse_submit(args: &[String]) -> Result<SubmitOptions, String> {
+    let mut options = SubmitOptions::default();
+    let mut i = 0;
+    while i < args.len() {
+        let a = &args[i];
+        match a.as_str() {
+            "--verify" => options.verify = true,
+            "--force" => options.force = true,
+            "--dry-run" => options.dry_run = true,
+            "--retry" => options.retry = true,
+            "--contest" => options.contest = Some(take_value(args, &mut i, "--contest")?),
+            "--delay" => {
+                options.delay = Some(
+                    take_value(args, &mut i, "--delay")?
+                        .parse()
+                        .map_err(|_| "invalid --delay value".to_string())?,
+                )
+            }
+            other if other.starts_with("--") => return Err(format!("unknown flag: {}", other)),
+            _ => {
+                if options.task.is_some() {
+                    return Err(format!("unexpected positional: {}", a));
+                }
+                options.task = Some(a.clone());
+            }
+        }
+        i += 1;
+    }
+    if options.contest.is_some() && options.task.is_some() {
+        return Err("provide either <name> or --contest NAME, not both".into());
+    }
+    if options.contest.is_some() && options.dry_run {
+        return Err("--dry-run doesn't support --contest".into());
+    }
+    if options.contest.is_some() && options.verify {
+        return Err("--contest always verifies, --verify doesn't apply".into());
+    }
+    if options.delay.is_some() && options.contest.is_none() {
+        return Err("--delay only applies to --contest".into());
+    }
+    if options.retry && (options.task.is_some() || options.contest.is_some() || options.dry_run) {
+        return Err("--retry doesn't take <name>, --contest or --dry-run".into());
+    }
+    Ok(options)
+}
+
+fn parse_task_name(args: &[String], cmd: &str) -> Result<String, String> {
+    match args {
+        [name] if !name.starts_with("--") => Ok(name.clone()),
+        [] => Err("missing task <name>".to_string()),
+        _ => Err(format!(
+            "{} takes exactly one task name (got: {:?})",
+            cmd, args
+        )),
+    }
+}
+
+fn parse_status(args: &[String]) -> Result<Option<String>, String> {
+    match args {
+        [] => Ok(None),
+        [name] if !name.starts_with("--") => Ok(Some(name.clone())),
+        _ => Err(format!(
+            "status takes at most one task name (got: {:?})",
+            args
+        )),
+    }
+}
+
+fn parse_test(args: &[String]) -> Result<(String, TestOptions), String> {
+    let mut name: Option<String> = None;
+    let mut options = TestOptions::default();
+    let mut i = 0;
+    while i < args.len() {
+        let a = &args[i];
+        match a.as_str() {
+            "--double-run" => options.double_run = true,
+            "--report" => options.report = true,
+            "--jobs" => {
+                options.jobs = Some(
+                    take_value(args, &mut i, "--jobs")?
+                        .parse()
+                        .map_err(|_| "invalid --jobs value".to_string())?,
+                )
+            }
+            "--pin" => options.pin = true,
+            "--only" => options.only = Some(take_value(args, &mut i, "--only")?),
+            "--accept" => options.accept = true,
+            other if other.starts_with("--") => return Err(format!("unknown flag: {}", other)),
+            _ => {
+                if name.is_some() {
+                    return Err(format!("unexpected positional: {}", a));
+                }
+                name = Some(a.clone());
+            }
+        }
+        i += 1;
+    }
+    let name = name.ok_or_else(|| "missing task <name>".to_string())?;
+    Ok((name, options))
+}
+
+fn parse_interact(args: &[String]) -> Result<(String, bool), String> {
+    let mut name: Option<String> = None;
+    let mut save = true;
+    for a in args {
+        match a.as_str() {
+            "--no-save" => save = false,
+            other if other.starts_with("--") => return Err(format!("unknown flag: {}", other)),
+            _ => {
+                if name.is_some() {
+                    return Err(format!("unexpected positional: {}", a));
+                }
+                name = Some(a.clone());
+            }
+        }
+    }
+    let name = name.ok_or_else(|| "missing task <name>".to_string())?;
+    Ok((name, save))
+}
+
+fn parse_run(args: &[String]) -> Result<(String, RunOptions), String> {
+    let mut name: Option<String> = None;
+    let mut options = RunOptions::default();
+    let mut i = 0;
+    while i < args.len() {
+        let a = &args[i];
+        match a.as_str() {
+            "--input" => options.input = InputSource::File(take_value(args, &mut i, "--input")?),
+            "--clipboard" => options.input = InputSource::Clipboard,
+            "--save" => options.save = true,
+            other if other.starts_with("--") => return Err(format!("unknown flag: {}", other)),
+            _ => {
+                if name.is_some() {
+                    return Err(format!("unexpected positional: {}", a));
+                }
+                name = Some(a.clone());
+            }
+        }
+        i += 1;
+    }
+    let name = name.ok_or_else(|| "missing task <name>".to_string())?;
+    Ok((name, options))
+}
+
+enum Calibrate {
+    Source,
+    Run { site: String, judge_ms: Option<u64> },
+}
+
+fn parse_calibrate(args: &[String]) -> Result<Calibrate, String> {
+    let mut site: Option<String> = None;
+    let mut judge_ms = None;
+    let mut source = false;
+    let mut i = 0;
+    while i < args.len() {
+        let a = &args[i];
+        match a.as_str() {
+            "--source" => source = true,
+            "--judge-ms" => {
+                judge_ms = Some(
+                    take_value(args, &mut i, "--judge-ms")?
+                        .parse()
+                        .map_err(|_| "invalid --judge-ms value".to_string())?,
+                )
+            }
+            other if other.starts_with("--") => return Err(format!("unknown flag: {}", other)),
+            _ => {
+                if site.is_some() {
+                    return Err(format!("unexpected positional: {}", a));
+                }
+                site = Some(a.clone());
+            }
+        }
+        i += 1;
+    }
+    if source {
+        return Ok(Calibrate::Source);
+    }
+    let site = site.ok_or_else(|| "missing <site>".to_string())?;
+    Ok(Calibrate::Run { site, judge_ms })
+}
+
 struct NewArgs {
     name: String,
     tests: usize,
@@ -45,6 +286,7 @@ struct NewArgs {
     output: IOType,
     interactive: bool,
     time_limit: u64,
+    memory_limit: Option<u64>,
     group: String,
 }
 
@@ -56,6 +298,7 @@ fn parse_new(args: &[String]) -> Result<NewArgs, String> {
     let mut output_file: Option<String> = None;
     let mut interactive = false;
     let mut time_limit = 2000u64;
+    let mut memory_limit: Option<u64> = None;
     let mut group = "Manual".to_string();
     let mut i = 0;
     while i < args.len() {
@@ -75,6 +318,13 @@ fn parse_new(args: &[String]) -> Result<NewArgs, String> {
             "--output-file" => output_file = Some(take_value(args, &mut i, "--output-file")?),
             "--interactive" => interactive = true,
             "--time-limit" => time_limit = take_value(args, &mut i, "--time-limit")?.parse().map_err(|_| "invalid --time-limit value".to_string())?,
+            "--memory-limit" => {
+                memory_limit = Some(
+                    take_value(args, &mut i, "--memory-limit")?
+                        .parse()
+                        .map_err(|_| "invalid --memory-limit value".to_string())?,
+                )
+            }
             "--group" => group = take_value(args, &mut i, "--group")?,
             other if other.starts_with("--") => return Err(format!("unknown flag: {}", other)),
             _ => {
@@ -95,7 +345,17 @@ fn parse_new(args: &[String]) -> Result<NewArgs, String> {
         Some(p) => IOType { io_type: IOEnum::File, file_name: Some(p), pattern: None },
         None => IOType { io_type: IOEnum::StdOut, file_name: None, pattern: None },
     };
-    Ok(NewArgs { name, tests, test_type, input, output, interactive, time_limit, group })
+    Ok(NewArgs {
+        name,
+        tests,
+        test_type,
+        input,
+        output,
+        interactive,
+        time_limit,
+        memory_limit,
+        group,
+    })
 }
 
 fn build_task(a: NewArgs) -> Task {
@@ -105,7 +365,9 @@ fn build_task(a: NewArgs) -> Task {
         url: String::new(),
         interactive: a.interactive,
         time_limit: a.time_limit,
-        tests: vec![Test { input: String::new(), output: String::new() }; a.tests],
+        memory_limit: a.memory_limit,
+        tests: vec![Test::new(TestSource::Manual); a.tests],
+        groups: Vec::new(),
         test_type: a.test_type,
         input: a.input,
         output: a.output,
@@ -193,20 +455,64 @@ const NEW_USAGE: &str = "Usage: rust-competitive-helper new <name>
     [--output-file PATH]                              (default stdout)
     [--interactive]                                   (default false)
     [--time-limit MS]                                 (default 2000)
+    [--memory-limit MB]                               (default none)
     [--group NAME]                                    (default \"Manual\")";
 
 const ARCHIVE_USAGE: &str = "Usage: rust-competitive-helper archive (<contest> | --task <name>)
     [--action skip|delete|archive|archive-tests]      (default archive)";
 
+const SUBMIT_USAGE: &str = "Usage: rust-competitive-helper submit [<name> | --contest <contest> | --retry]
+    [--verify]                                        (verify the bundle first)
+    [--force]                                         (submit even if verification fails)
+    [--dry-run]                                       (show the judge and command, don't submit)
+    [--delay SECS]                                    (between contest submissions, default submit_delay)";
+
+const TEST_USAGE: &str = "Usage: rust-competitive-helper test <name>
+    [--double-run]                                    (debug build with overflow checks, then release)
+    [--report]                                        (write report.json and JUnit report.xml)
+    [--jobs N]                                        (default test_jobs from config.toml)
+    [--pin]                                           (pin each worker to its own CPU)
+    [--only TAG]                                      (run only tests with this source or tag)
+    [--accept]                                        (store outputs of tests without an answer)";
+
+const WATCH_USAGE: &str = "Usage: rust-competitive-helper watch <name>";
+
+const INTERACT_USAGE: &str = "Usage: rust-competitive-helper interact <name>
+    [--no-save]                                       (don't store the transcript)";
+
+const RUN_USAGE: &str = "Usage: rust-competitive-helper run <name>
+    [--input FILE | --clipboard]                      (default stdin)
+    [--save]                                          (store input and output as a new test)";
+
+const CALIBRATE_USAGE: &str = "Usage: rust-competitive-helper calibrate (<site> | --source)
+    [--judge-ms MS]                                   (judge's time for the benchmark, saves the multiplier)";
+
+const STATUS_USAGE: &str = "Usage: rust-competitive-helper status [<name>]";
+
 const HELP: &str = "rust-competitive-helper — competitive programming task helper
 
 Usage:
     rust-competitive-helper                 launch the interactive menu
-    rust-competitive-helper submit          submit main/src/main.rs
+    rust-competitive-helper submit [<name>] rebuild a task's bundle and submit it
+    rust-competitive-helper verify          compile main/src/main.rs standalone and test it
     rust-competitive-helper new <name> ...  create a task non-interactively
     rust-competitive-helper archive ...     archive a contest or single task
+    rust-competitive-helper test <name>     build a task and run its tests
+    rust-competitive-helper watch <name>    re-run a task's tests whenever it changes
+    rust-competitive-helper interact <name> run an interactive task from the terminal
+    rust-competitive-helper run <name>      run a task once on custom input
+    rust-competitive-helper calibrate <site> time a benchmark to scale time limits to a judge
+    rust-competitive-helper status [<name>] show submissions of a task or the latest of each
     rust-competitive-helper help            show this help
 
+submit flags:
+    --verify                                        verify the bundle first
+    --force                                         submit even if verification fails, also with --contest
+    --dry-run                                       show the judge, command and bundle without submitting
+    --contest NAME                                  build, verify and submit every task of a contest
+    --delay SECS                                    seconds between contest submissions (default submit_delay)
+    --retry                                         resend submissions queued while the judge was unreachable
+
 new flags:
     --tests N                                       (default 0)
     --test-type single|multi-number|multi-eof       (default single)
@@ -214,8 +520,29 @@ new flags:
     --output-file PATH                              (default stdout)
     --interactive                                   (default false)
     --time-limit MS                                 (default 2000)
+    --memory-limit MB                               (default none)
     --group NAME                                    (default \"Manual\")
 
 archive flags:
     --task NAME                                     archive a single task
-    --action skip|delete|archive|archive-tests      (default archive)";
+    --action skip|delete|archive|archive-tests      (default archive)
+
+test flags:
+    --double-run                                    debug build with overflow checks, then release
+    --report                                        write report.json and JUnit report.xml
+    --jobs N                                        tests to run in parallel (default test_jobs)
+    --pin                                           pin each worker to its own CPU
+    --only TAG                                      run only tests with this source or tag
+    --accept                                        store outputs of tests without an answer
+
+interact flags:
+    --no-save                                       don't store the transcript
+
+run flags:
+    --input FILE                                    read the input from FILE (default stdin)
+    --clipboard                                     read the input from the clipboard
+    --save                                          store input and output as a new test
+
+calibrate flags:
+    --source                                        print the benchmark program
+    --judge-ms MS                                   judge's time for the benchmark, saves the multiplier";
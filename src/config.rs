@@ -1,6 +1,10 @@
 use itertools::Itertools;
 use std::path::Path;
-use std::{collections::HashMap, fs, process::Command};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    process::Command,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +15,116 @@ pub struct Config {
     open_task_command: Vec<String>,
     pub input_file_extension: String,
     pub output_file_extension: String,
+    /// Memory limit in megabytes for tasks that don't specify their own.
+    pub memory_limit: Option<u64>,
+    /// Compile and test the bundle standalone before every submission.
+    #[serde(default)]
+    pub verify_before_submit: bool,
+    /// How wrong answers are shown: `side-by-side` or `unified`.
+    #[serde(default)]
+    pub diff_style: DiffStyle,
+    /// Number of tests local runs execute in parallel.
+    #[serde(default = "default_test_jobs")]
+    pub test_jobs: usize,
+    /// Pin each parallel test worker to its own CPU (Linux only).
+    #[serde(default)]
+    pub pin_cpus: bool,
+    /// Seconds `submit --contest` waits between submissions.
+    #[serde(default = "default_submit_delay")]
+    pub submit_delay: u64,
+    /// Seconds between retries of queued submissions while the menu runs,
+    /// 0 turns them off.
+    #[serde(default = "default_retry_interval")]
+    pub retry_interval: u64,
+    /// Per-site settings, keyed by the site name `submit` detects from the
+    /// task URL (e.g. `codeforces`, `atcoder`).
+    #[serde(default)]
+    pub sites: BTreeMap<String, SiteConfig>,
+    /// Mirrors and other domains of judges, tried in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<AliasConfig>,
+    /// Judges submissions go to. Entries here take precedence over the
+    /// built-in ones, which are used for hosts no entry matches.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub judges: Vec<JudgeConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffStyle {
+    #[default]
+    SideBySide,
+    Unified,
+}
+
+/// How a judge builds submissions, used to verify bundles with the same
+/// compiler setup.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SiteConfig {
+    /// Name of a locally installed rustup toolchain, e.g. `1.75.0`.
+    pub toolchain: Option<String>,
+    pub edition: Option<String>,
+    #[serde(default)]
+    pub rustc_flags: Vec<String>,
+    /// Judge time divided by local time for the same program, as measured
+    /// by `calibrate`. Local time limits are divided by it.
+    pub time_multiplier: Option<f64>,
+    /// Largest source the judge accepts, in bytes, replacing the judge's own
+    /// `source_limit`.
+    pub source_limit: Option<u64>,
+}
+
+/// A host pattern that belongs to a judge, which is also the site whose
+/// `sites` section applies.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AliasConfig {
+    /// Regex matched against the host of the task URL.
+    pub host: String,
+    /// Judge name, e.g. `codeforces`.
+    pub judge: String,
+}
+
+/// How to submit to a judge. Arguments of `command` and `login` can use
+/// `$URL`, `$FILE`, `$LANG` and `$PROBLEM_ID`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JudgeConfig {
+    /// Judge name, e.g. `codeforces`.
+    pub name: String,
+    /// Regex matched against the host of the task URL.
+    pub host: String,
+    pub command: Vec<String>,
+    /// Command run before `command`, e.g. to log in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub login: Option<Vec<String>>,
+    /// Language id substituted for `$LANG`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Regex with a `problem_id` group, matched against the task URL to get
+    /// `$PROBLEM_ID`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub problem_id: Option<String>,
+    /// Shown when the command can't be run, e.g. where to install it from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub install_hint: Option<String>,
+    /// Largest source the judge accepts, in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_limit: Option<u64>,
+    /// Names of judges tried in order when this one fails to submit, before
+    /// falling back to the clipboard.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback: Vec<String>,
+}
+
+fn default_test_jobs() -> usize {
+    1
+}
+
+fn default_submit_delay() -> u64 {
+    10
+}
+
+fn default_retry_interval() -> u64 {
+    60
 }
 
 impl Default for Config {
@@ -43,6 +157,16 @@ impl Default for Config {
             open_task_command,
             input_file_extension: ".in".to_string(),
             output_file_extension: ".out".to_string(),
+            memory_limit: None,
+            verify_before_submit: false,
+            diff_style: DiffStyle::SideBySide,
+            test_jobs: default_test_jobs(),
+            pin_cpus: false,
+            submit_delay: default_submit_delay(),
+            retry_interval: default_retry_interval(),
+            sites: BTreeMap::new(),
+            aliases: Vec::new(),
+            judges: Vec::new(),
         }
     }
 }
@@ -87,6 +211,33 @@ fn global_config_path() -> Option<String> {
     }
 }
 
+fn with_site_value(
+    content: &str,
+    site: &str,
+    key: &str,
+    value: toml_edit::Value,
+) -> Result<String, String> {
+    let mut document: toml_edit::DocumentMut = content
+        .parse()
+        .map_err(|err| format!("Can't parse {}: {}", CONFIG_FILE, err))?;
+    let sites = document.entry("sites").or_insert_with(|| {
+        let mut sites = toml_edit::Table::new();
+        sites.set_implicit(true);
+        toml_edit::Item::Table(sites)
+    });
+    let table = sites
+        .as_table_like_mut()
+        .and_then(|sites| {
+            sites
+                .entry(site)
+                .or_insert(toml_edit::table())
+                .as_table_like_mut()
+        })
+        .ok_or_else(|| format!("sites.{} in {} isn't a table", site, CONFIG_FILE))?;
+    table.insert(key, toml_edit::Item::Value(value));
+    Ok(document.to_string())
+}
+
 impl Config {
     pub fn load() -> Self {
         if Path::new(CONFIG_FILE).exists() {
@@ -108,6 +259,24 @@ impl Config {
         }
     }
 
+    pub fn site(&self, site: &str) -> SiteConfig {
+        self.sites.get(site).cloned().unwrap_or_default()
+    }
+
+    /// Sets `sites.<site>.<key>` in config.toml, keeping the rest of the file,
+    /// comments included, as written.
+    pub fn set_site_value(
+        site: &str,
+        key: &str,
+        value: impl Into<toml_edit::Value>,
+    ) -> Result<(), String> {
+        let content = fs::read_to_string(CONFIG_FILE)
+            .map_err(|err| format!("Can't read {}: {}", CONFIG_FILE, err))?;
+        let content = with_site_value(&content, site, key, value.into())?;
+        fs::write(CONFIG_FILE, content)
+            .map_err(|err| format!("Can't write {}: {}", CONFIG_FILE, err))
+    }
+
     pub fn from_toml(content: &str) -> Self {
         toml::from_str(content).expect("Can't parse config")
     }
@@ -187,7 +356,34 @@ impl Config {
 
 #[cfg(test)]
 mod tests {
-    use super::Config;
+    use super::{with_site_value, Config};
+
+    #[test]
+    fn test_with_site_value() {
+        let content = r#"# judges I use
+[[judges]]
+name = "cf-oj"
+host = '^$'
+command = ["oj", "submit", "$URL", "$FILE"]
+
+[sites.codeforces]
+edition = "2021" # for the new toolchain
+time_multiplier = 1.0
+"#;
+        let updated =
+            with_site_value(content, "codeforces", "time_multiplier", 1.25.into()).unwrap();
+        assert_eq!(
+            updated,
+            content.replace("time_multiplier = 1.0", "time_multiplier = 1.25")
+        );
+        let updated = with_site_value(&updated, "atcoder", "time_multiplier", 0.8.into()).unwrap();
+        assert!(updated.starts_with("# judges I use\n[[judges]]\nname = \"cf-oj\""));
+        assert!(updated.ends_with("\n[sites.atcoder]\ntime_multiplier = 0.8\n"));
+        assert_eq!(
+            with_site_value("", "timus", "time_multiplier", 2.0.into()).unwrap(),
+            "[sites.timus]\ntime_multiplier = 2.0\n"
+        );
+    }
 
     #[test]
     fn test_roundtrip() {
@@ -210,6 +406,89 @@ output_file_extension = ".answer"
         assert_eq!(config.output_file_extension, ".answer");
     }
 
+    #[test]
+    fn test_parse_site_settings() {
+        let toml = r#"
+open_task_command = ["echo"]
+input_file_extension = ".in"
+output_file_extension = ".out"
+
+[sites.codeforces]
+toolchain = "1.75.0"
+edition = "2021"
+rustc_flags = ["-C", "target-cpu=native"]
+time_multiplier = 1.5
+"#;
+        let config = Config::from_toml(toml);
+        let codeforces = config.site("codeforces");
+        assert_eq!(codeforces.time_multiplier, Some(1.5));
+        assert_eq!(codeforces.toolchain.as_deref(), Some("1.75.0"));
+        assert_eq!(codeforces.edition.as_deref(), Some("2021"));
+        assert_eq!(codeforces.rustc_flags, vec!["-C", "target-cpu=native"]);
+        let unknown = config.site("atcoder");
+        assert!(unknown.toolchain.is_none());
+        assert!(unknown.rustc_flags.is_empty());
+        assert!(unknown.time_multiplier.is_none());
+        let parsed = Config::from_toml(&config.to_toml());
+        assert_eq!(
+            parsed.site("codeforces").toolchain.as_deref(),
+            Some("1.75.0")
+        );
+    }
+
+    #[test]
+    fn test_parse_judges() {
+        let toml = r#"
+open_task_command = ["echo"]
+input_file_extension = ".in"
+output_file_extension = ".out"
+
+[[judges]]
+name = "dmoj"
+host = "(^|\\.)dmoj\\.ca$"
+command = ["dmoj-submit", "submit", "--problem", "$PROBLEM_ID", "$FILE"]
+problem_id = "/problem/(?P<problem_id>\\w+)"
+"#;
+        let config = Config::from_toml(toml);
+        assert_eq!(config.judges.len(), 1);
+        assert_eq!(config.judges[0].host, "(^|\\.)dmoj\\.ca$");
+        assert!(config.judges[0].login.is_none());
+        let parsed = Config::from_toml(&config.to_toml());
+        assert_eq!(parsed.judges[0].command[3], "$PROBLEM_ID");
+    }
+
+    #[test]
+    fn test_parse_aliases() {
+        let toml = r#"
+open_task_command = ["echo"]
+input_file_extension = ".in"
+output_file_extension = ".out"
+
+[sites.codeforces]
+edition = "2021"
+
+[[aliases]]
+host = '^codeforc\.es$'
+judge = "cf-mirror"
+
+[[aliases]]
+host = 'codeforc\.es$'
+judge = "codeforces"
+"#;
+        let config = Config::from_toml(toml);
+        let judges = |config: &Config| {
+            config
+                .aliases
+                .iter()
+                .map(|alias| alias.judge.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(judges(&config), vec!["cf-mirror", "codeforces"]);
+        assert_eq!(config.aliases[0].host, r"^codeforc\.es$");
+        let parsed = Config::from_toml(&config.to_toml());
+        assert_eq!(judges(&parsed), vec!["cf-mirror", "codeforces"]);
+    }
+
     #[test]
     fn test_parse_old_config_without_extensions() {
         // Old confy configs won't have the new fields - deserialization will fail
@@ -1,5 +1,5 @@
 use crate::task_creator;
-use rust_competitive_helper_util::Task;
+use rust_competitive_helper_util::{Task, TestSource};
 use std::io::Read;
 use std::net::{TcpListener, TcpStream};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -26,7 +26,10 @@ fn handle(mut stream: TcpStream) {
 }
 
 fn process(request: &str) {
-    let task: Task = serde_json::from_slice(request.as_bytes()).unwrap();
+    let mut task: Task = serde_json::from_slice(request.as_bytes()).unwrap();
+    for test in &mut task.tests {
+        test.source.get_or_insert(TestSource::Sample);
+    }
     task_creator::create(task);
 }
 
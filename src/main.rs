@@ -3,6 +3,7 @@ mod cli;
 mod config;
 mod listener;
 mod menu;
+mod runner;
 mod submit;
 mod task_creator;
 
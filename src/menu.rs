@@ -7,21 +7,32 @@ const OPTIONS: [&str; 4] = ["Submit", "Create new task", "Archive tasks", "Exit"
 
 pub fn run_menu() {
     listener::start_listener();
+    submit::start_background_retry();
 
     loop {
+        submit::print_retry_notices();
+        let mut items = OPTIONS.map(String::from).to_vec();
+        match submit::pending() {
+            Ok(0) => {}
+            Ok(pending) => items.insert(1, format!("Retry queued submissions ({})", pending)),
+            Err(err) => submit::failure(&err),
+        }
         let selection = Select::with_theme(&ColorfulTheme::default())
             .with_prompt("Select option:")
             .default(0)
-            .items(&OPTIONS)
+            .items(&items)
             .interact_on_opt(&Term::stdout())
             .unwrap();
-        match selection {
-            Some(0) => submit::submit(),
-            Some(1) => task_creator::create_task_wizard(),
-            Some(2) => archiver::archive(),
-            Some(3) => return,
-            None => continue,
-            _ => unreachable!(),
+        let Some(selection) = selection else {
+            continue;
+        };
+        submit::print_retry_notices();
+        match items[selection].as_str() {
+            "Submit" => submit::submit(&submit::SubmitOptions::default()),
+            "Create new task" => task_creator::create_task_wizard(),
+            "Archive tasks" => archiver::archive(),
+            "Exit" => return,
+            _ => submit::retry_now(),
         }
     }
 }
@@ -0,0 +1,174 @@
+use crate::config::Config;
+use crate::runner::io::IoFiles;
+use crate::runner::verify::ScratchDir;
+use crate::runner::{
+    build, limits, load_task_or_fail, memory, read_to_end, task_dir, wait_with_limit, Profile,
+};
+use clipboard::{ClipboardContext, ClipboardProvider};
+use rust_competitive_helper_util::{save_task, Task, Test, TestSource};
+use std::io::{IsTerminal, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Where `run` takes the input from.
+#[derive(Default)]
+pub enum InputSource {
+    #[default]
+    Stdin,
+    File(String),
+    Clipboard,
+}
+
+#[derive(Default)]
+pub struct RunOptions {
+    pub input: InputSource,
+    /// Store the input and the produced output as a new test.
+    pub save: bool,
+}
+
+fn read_input(source: &InputSource) -> Result<String, String> {
+    match source {
+        InputSource::Stdin => {
+            if std::io::stdin().is_terminal() {
+                println!("Enter the input, finish with Ctrl+D:");
+            }
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|err| format!("Can't read stdin: {}", err))?;
+            Ok(input)
+        }
+        InputSource::File(path) => {
+            std::fs::read_to_string(path).map_err(|err| format!("Can't read {}: {}", path, err))
+        }
+        InputSource::Clipboard => ClipboardProvider::new()
+            .and_then(|mut ctx: ClipboardContext| ctx.get_contents())
+            .map_err(|err| format!("Can't read the clipboard: {}", err)),
+    }
+}
+
+fn next_test_id(config: &Config, task_name: &str) -> usize {
+    let dir = format!("{}/tests", task_dir(task_name));
+    let mut id = 1;
+    while Path::new(&format!("{}/{}{}", dir, id, config.input_file_extension)).exists() {
+        id += 1;
+    }
+    id
+}
+
+fn save_test(
+    config: &Config,
+    task_name: &str,
+    task: &mut Task,
+    input: &str,
+    output: &str,
+) -> Result<(), String> {
+    let dir = format!("{}/tests", task_dir(task_name));
+    std::fs::create_dir_all(&dir).map_err(|err| format!("Can't create {}: {}", dir, err))?;
+    let id = next_test_id(config, task_name);
+    for (extension, content) in [
+        (&config.input_file_extension, input),
+        (&config.output_file_extension, output),
+    ] {
+        let path = format!("{}/{}{}", dir, id, extension);
+        std::fs::write(&path, content).map_err(|err| format!("Can't write {}: {}", path, err))?;
+    }
+    task.tests.push(Test {
+        input: input.to_string(),
+        output: output.to_string(),
+        name: Some(id.to_string()),
+        ..Test::new(TestSource::Manual)
+    });
+    save_task(task_dir(task_name), task)?;
+    println!("Saved as test {}", id);
+    Ok(())
+}
+
+/// Runs the solution in a scratch directory, so file IO tasks find their
+/// input file and don't leave output files behind in the workspace.
+fn execute(
+    config: &Config,
+    task: &Task,
+    binary: &Path,
+    input: String,
+) -> Result<Option<String>, String> {
+    let io = IoFiles::new(task)?;
+    let input_file = io.input.as_deref();
+    let output_file = io.output.as_deref();
+    let dir = ScratchDir::new("run")?;
+    if let Some(name) = input_file {
+        std::fs::write(dir.0.join(name), &input)
+            .map_err(|err| format!("Can't write {}: {}", name, err))?;
+    }
+    let binary = binary
+        .canonicalize()
+        .map_err(|err| format!("Can't find {}: {}", binary.display(), err))?;
+    let mut child = Command::new(&binary)
+        .current_dir(&dir.0)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|err| format!("Couldn't run {}: {}", binary.display(), err))?;
+    let mut stdin = child.stdin.take().unwrap();
+    let piped = input_file.is_none();
+    let writer = std::thread::spawn(move || {
+        if piped {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+    });
+    let stdout = read_to_end(child.stdout.take().unwrap());
+    let limit = limits(config, task).time;
+    let execution = wait_with_limit(&mut child, limit);
+    let _ = writer.join();
+    let stdout = stdout.join().unwrap_or_default();
+    let output = match output_file {
+        Some(name) => {
+            if !stdout.is_empty() {
+                println!("stdout:");
+                print!("{}", stdout);
+            }
+            println!("{}:", name);
+            std::fs::read_to_string(dir.0.join(name)).unwrap_or_default()
+        }
+        None => stdout,
+    };
+    print!("{}", output);
+    if !output.is_empty() && !output.ends_with('\n') {
+        println!();
+    }
+    let memory = execution
+        .memory
+        .map(|memory| format!(", {}", memory::format_memory(memory)))
+        .unwrap_or_default();
+    match execution.status {
+        None => {
+            println!("Time limit exceeded after {} ms", limit.as_millis());
+            Ok(None)
+        }
+        Some(status) => {
+            println!("{} ms{}, {}", execution.time.as_millis(), memory, status);
+            Ok(status.success().then_some(output))
+        }
+    }
+}
+
+/// Builds the task and runs it once on a custom input, printing the output
+/// and timing. With `save` a successful run is stored as a new test.
+pub fn run(task_name: &str, options: &RunOptions) -> Result<(), String> {
+    let config = Config::load();
+    let mut task = load_task_or_fail(task_name)?;
+    if task.interactive {
+        return Err("Interactive tasks can't be run on a fixed input, use interact".to_string());
+    }
+    let binary = build(task_name, Profile::Release)?;
+    let input = read_input(&options.input)?;
+    let output = execute(&config, &task, &binary, input.clone())?;
+    if options.save {
+        match output {
+            Some(output) => save_test(&config, task_name, &mut task, &input, &output)?,
+            None => println!("Run failed, not saving it as a test"),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use std::process::Command;
+
+/// Makes the process spawned by `command` run only on `cpu`. Pinning is only
+/// supported on Linux; elsewhere this does nothing.
+#[cfg(target_os = "linux")]
+pub(crate) fn pin(command: &mut Command, cpu: usize) {
+    use std::os::unix::process::CommandExt;
+
+    unsafe {
+        command.pre_exec(move || {
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            libc::CPU_ZERO(&mut set);
+            libc::CPU_SET(cpu, &mut set);
+            if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn pin(_command: &mut Command, _cpu: usize) {}
+
+/// Ids of the CPUs this process may run on, which under `taskset` or a
+/// container's cpuset needn't start at 0.
+#[cfg(target_os = "linux")]
+pub(crate) fn allowed_cpus() -> Vec<usize> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    let res =
+        unsafe { libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) };
+    let cpus = if res == 0 {
+        (0..libc::CPU_SETSIZE as usize)
+            .filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if cpus.is_empty() {
+        (0..cpu_count()).collect()
+    } else {
+        cpus
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn allowed_cpus() -> Vec<usize> {
+    (0..cpu_count()).collect()
+}
+
+fn cpu_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::{allowed_cpus, pin};
+    use std::process::Command;
+
+    #[test]
+    fn test_pin_to_allowed_cpus() {
+        let cpus = allowed_cpus();
+        assert!(!cpus.is_empty());
+        for cpu in [cpus[0], cpus[cpus.len() - 1]] {
+            let mut command = Command::new("true");
+            pin(&mut command, cpu);
+            assert!(command.status().unwrap().success(), "cpu {}", cpu);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::config::Config;
+use crate::runner::{load_task_or_fail, task_dir, test_index, TestResult, Verdict};
+use dialoguer::console::Term;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::Confirm;
+use rust_competitive_helper_util::save_task;
+use std::io::IsTerminal;
+
+fn confirm(test: &str) -> bool {
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Store the output of test {} as its answer?", test))
+        .default(false)
+        .interact_on_opt(&Term::stdout())
+        .ok()
+        .flatten()
+        .unwrap_or(false)
+}
+
+/// Offers to store the output of every test that had no expected output as
+/// its answer, in `tests/` and in task.json. With `accept` every such output
+/// is stored without asking.
+pub(crate) fn offer_to_store(
+    config: &Config,
+    task_name: &str,
+    results: &[TestResult],
+    accept: bool,
+) -> Result<(), String> {
+    let unknown = results
+        .iter()
+        .filter(|result| result.verdict == Verdict::Unknown)
+        .filter_map(|result| Some((&result.name, &result.outputs.as_ref()?.actual)))
+        .collect::<Vec<_>>();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    if !accept && !std::io::stdin().is_terminal() {
+        println!("Run with --accept to store the outputs of tests without an expected output");
+        return Ok(());
+    }
+    let mut task = load_task_or_fail(task_name)?;
+    let mut stored = false;
+    for (name, output) in unknown {
+        if !accept && !confirm(name) {
+            continue;
+        }
+        let path = format!(
+            "{}/tests/{}{}",
+            task_dir(task_name),
+            name,
+            config.output_file_extension
+        );
+        std::fs::write(&path, output).map_err(|err| format!("Can't write {}: {}", path, err))?;
+        if let Some(index) = test_index(&task, name) {
+            task.tests[index].output = output.clone();
+            stored = true;
+        }
+        println!("Stored {}", path);
+    }
+    if stored {
+        save_task(task_dir(task_name), &task)?;
+    }
+    Ok(())
+}
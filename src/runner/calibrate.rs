@@ -0,0 +1,123 @@
+use crate::config::Config;
+use crate::runner::verify::{rustc_args, ScratchDir};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Fixed workload timed by `calibrate`: a sieve, a sort and a random walk
+/// over a large array, roughly the mix of a typical solution. It reads
+/// nothing, so the same file can be run in a judge's custom invocation.
+pub const BENCHMARK: &str = r#"fn main() {
+    const N: usize = 30_000_000;
+    let mut composite = vec![false; N + 1];
+    let mut checksum = 0u64;
+    for i in 2..=N {
+        if !composite[i] {
+            checksum = checksum.wrapping_add(i as u64);
+            let mut j = i * i;
+            while j <= N {
+                composite[j] = true;
+                j += i;
+            }
+        }
+    }
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut values: Vec<u64> = (0..5_000_000).map(|_| next()).collect();
+    values.sort_unstable();
+    for (i, value) in values.iter().enumerate().step_by(1000) {
+        checksum = checksum.wrapping_mul(31).wrapping_add(value ^ i as u64);
+    }
+    let links: Vec<u32> = (0..1u32 << 22).map(|_| (next() % (1 << 22)) as u32).collect();
+    let mut at = 0u32;
+    for _ in 0..20_000_000 {
+        at = links[at as usize] ^ (at & 7);
+    }
+    println!("{}", checksum.wrapping_add(at as u64));
+}
+"#;
+
+const RUNS: usize = 3;
+
+/// Compiles the benchmark with the site's toolchain and flags and returns
+/// the fastest of a few runs.
+fn local_time(config: &Config, site: &str) -> Result<Duration, String> {
+    let dir = ScratchDir::new("calibrate")?;
+    let source = dir.0.join("benchmark.rs");
+    std::fs::write(&source, BENCHMARK)
+        .map_err(|err| format!("Can't write {}: {}", source.display(), err))?;
+    let binary = dir
+        .0
+        .join(format!("benchmark{}", std::env::consts::EXE_SUFFIX));
+    let status = Command::new("rustc")
+        .args(rustc_args(&config.site(site)))
+        .arg("-o")
+        .arg(&binary)
+        .arg(&source)
+        .status()
+        .map_err(|err| format!("Couldn't run rustc: {}", err))?;
+    if !status.success() {
+        return Err("Benchmark doesn't compile".to_string());
+    }
+    let mut best = Duration::MAX;
+    for run in 1..=RUNS {
+        let start = Instant::now();
+        let status = Command::new(&binary)
+            .stdout(Stdio::null())
+            .status()
+            .map_err(|err| format!("Couldn't run benchmark: {}", err))?;
+        let time = start.elapsed();
+        if !status.success() {
+            return Err(format!("Benchmark failed: {}", status));
+        }
+        println!("Run {}/{}: {} ms", run, RUNS, time.as_millis());
+        best = best.min(time);
+    }
+    Ok(best)
+}
+
+fn multiplier(judge_ms: u64, local: Duration) -> f64 {
+    judge_ms as f64 / local.as_secs_f64() / 1000.0
+}
+
+/// Times the benchmark locally. With `judge_ms`, the time the judge reports
+/// for the same program, stores the site's time multiplier in config.
+pub fn calibrate(site: &str, judge_ms: Option<u64>) -> Result<(), String> {
+    let config = Config::load();
+    let local = local_time(&config, site)?;
+    println!("Local time: {} ms", local.as_millis());
+    let Some(judge_ms) = judge_ms else {
+        println!(
+            "Run the program from `calibrate --source` on {} (e.g. in its custom invocation) \
+             and pass the reported time with --judge-ms",
+            site
+        );
+        return Ok(());
+    };
+    if judge_ms == 0 {
+        return Err("--judge-ms must be positive".to_string());
+    }
+    let multiplier = (multiplier(judge_ms, local) * 100.0).round() / 100.0;
+    Config::set_site_value(site, "time_multiplier", multiplier)?;
+    println!(
+        "{} is {:.2}x this machine, saved as sites.{}.time_multiplier",
+        site, multiplier, site
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::multiplier;
+    use std::time::Duration;
+
+    #[test]
+    fn test_multiplier() {
+        assert_eq!(multiplier(1500, Duration::from_millis(1000)), 1.5);
+        assert_eq!(multiplier(250, Duration::from_millis(500)), 0.5);
+    }
+}
@@ -0,0 +1,344 @@
+use crate::config::DiffStyle;
+use crossterm::style::Stylize;
+
+/// Lines of context shown around the first mismatching line.
+const CONTEXT: usize = 5;
+const DEFAULT_WIDTH: usize = 100;
+
+pub(crate) struct Outputs {
+    pub expected: String,
+    pub actual: String,
+}
+
+struct Mismatch {
+    line: usize,
+    token: usize,
+}
+
+fn lines(s: &str) -> Vec<&str> {
+    s.lines().collect()
+}
+
+/// First line (and token within it) whose tokens differ. Missing lines
+/// count as empty, so an output that stops early mismatches on the first
+/// line that is absent.
+fn first_mismatch(expected: &str, actual: &str) -> Option<Mismatch> {
+    let expected = lines(expected);
+    let actual = lines(actual);
+    for line in 0..expected.len().max(actual.len()) {
+        let e = expected.get(line).copied().unwrap_or_default();
+        let a = actual.get(line).copied().unwrap_or_default();
+        let mut e_tokens = e.split_whitespace();
+        let mut a_tokens = a.split_whitespace();
+        let mut token = 0;
+        loop {
+            match (e_tokens.next(), a_tokens.next()) {
+                (None, None) => break,
+                (e, a) if e == a => token += 1,
+                _ => return Some(Mismatch { line, token }),
+            }
+        }
+    }
+    None
+}
+
+/// Byte range of the `index`-th whitespace-separated token of `line`.
+fn token_span(line: &str, index: usize) -> Option<(usize, usize)> {
+    let mut count = 0;
+    let mut start = None;
+    for (i, c) in line
+        .char_indices()
+        .chain(std::iter::once((line.len(), ' ')))
+    {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(i),
+            (true, Some(s)) => {
+                if count == index {
+                    return Some((s, i));
+                }
+                count += 1;
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn quote(token: Option<&str>) -> String {
+    match token {
+        Some(token) => format!("'{}'", token),
+        None => "nothing".to_string(),
+    }
+}
+
+/// One-line description of where the outputs first differ.
+pub(crate) fn describe(expected: &str, actual: &str) -> String {
+    let Some(mismatch) = first_mismatch(expected, actual) else {
+        return "outputs differ only in line breaks".to_string();
+    };
+    let token = |s: &str| {
+        s.lines()
+            .nth(mismatch.line)
+            .and_then(|line| line.split_whitespace().nth(mismatch.token))
+            .map(str::to_string)
+    };
+    format!(
+        "line {}, token {}: expected {}, found {}",
+        mismatch.line + 1,
+        mismatch.token + 1,
+        quote(token(expected).as_deref()),
+        quote(token(actual).as_deref()),
+    )
+}
+
+/// Explains a byte-level difference between outputs the token checker
+/// accepts, so CRLF output or stray spaces don't go unnoticed.
+pub(crate) fn whitespace_note(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+    if expected.contains("\r\n") != actual.contains("\r\n") {
+        let (crlf, lf) = if actual.contains("\r\n") {
+            ("output", "expected answer")
+        } else {
+            ("expected answer", "output")
+        };
+        return Some(format!(
+            "note: {} uses CRLF line endings, {} uses LF",
+            crlf, lf
+        ));
+    }
+    let trimmed = |s: &str| s.lines().map(str::trim_end).collect::<Vec<_>>().join("\n");
+    if trimmed(expected).trim_end() == trimmed(actual).trim_end() {
+        Some("note: output differs from the answer only in trailing whitespace".to_string())
+    } else {
+        Some("note: output differs from the answer only in whitespace".to_string())
+    }
+}
+
+fn only_whitespace_differs(e: &str, a: &str) -> bool {
+    e != a && e.split_whitespace().eq(a.split_whitespace())
+}
+
+fn visible(line: &str) -> String {
+    line.replace('\r', "\\r").replace('\t', "\\t")
+}
+
+/// Cuts `text` down to `width` characters around the character range
+/// `span`, marking cut ends with `…`. Returns the parts before, within and
+/// after the span.
+fn window(text: &str, span: Option<(usize, usize)>, width: usize) -> (String, String, String) {
+    let chars: Vec<char> = text.chars().collect();
+    let (span_start, span_end) = span.unwrap_or((0, 0));
+    let (mut start, mut end) = (0, chars.len());
+    if chars.len() > width {
+        let room = width.saturating_sub(2).max(1);
+        let margin = room.saturating_sub(span_end - span_start) / 2;
+        start = span_start.saturating_sub(margin).min(chars.len() - room);
+        end = start + room;
+    }
+    let part = |from: usize, to: usize| chars[from.clamp(start, end)..to.clamp(start, end)].iter();
+    let mut before = if start > 0 {
+        "…".to_string()
+    } else {
+        String::new()
+    };
+    before.extend(part(start, span_start));
+    let within = part(span_start, span_end).collect();
+    let mut after: String = part(span_end, end).collect();
+    if end < chars.len() {
+        after.push('…');
+    }
+    (before, within, after)
+}
+
+/// Renders `line` cut down to `width` characters around token `token`, which
+/// is highlighted when set, and padded to `width` when `pad`.
+fn render(line: &str, token: Option<usize>, width: usize, pad: bool) -> String {
+    let text = visible(line);
+    let span = token
+        .and_then(|token| token_span(&text, token))
+        .map(|(start, end)| {
+            let chars = text[..start].chars().count();
+            (chars, chars + text[start..end].chars().count())
+        });
+    let (before, within, after) = window(&text, span, width);
+    let shown = before.chars().count() + within.chars().count() + after.chars().count();
+    let padding = if pad {
+        " ".repeat(width.saturating_sub(shown))
+    } else {
+        String::new()
+    };
+    if within.is_empty() {
+        format!("{}{}{}", before, after, padding)
+    } else {
+        format!("{}{}{}{}", before, within.black().on_red(), after, padding)
+    }
+}
+
+fn width() -> usize {
+    crossterm::terminal::size()
+        .map(|(columns, _)| columns as usize)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// Lines of a produced output shown when there is no answer to compare to.
+const OUTPUT_LINES: usize = 20;
+
+/// Prints the beginning of an output that had nothing to be compared to.
+pub(crate) fn print_output(output: &str) {
+    let lines = lines(output);
+    println!("    output:");
+    let width = width().saturating_sub(6).max(10);
+    for line in lines.iter().take(OUTPUT_LINES) {
+        println!("    > {}", render(line, None, width, false));
+    }
+    if lines.len() > OUTPUT_LINES {
+        println!("    ... {} lines below", lines.len() - OUTPUT_LINES);
+    }
+}
+
+/// Prints a diff of the outputs around their first mismatch.
+pub(crate) fn print(outputs: &Outputs, style: DiffStyle) {
+    let expected = lines(&outputs.expected);
+    let actual = lines(&outputs.actual);
+    let Some(mismatch) = first_mismatch(&outputs.expected, &outputs.actual) else {
+        return;
+    };
+    let total = expected.len().max(actual.len());
+    let from = mismatch.line.saturating_sub(CONTEXT);
+    let to = (mismatch.line + CONTEXT + 1).min(total);
+    if from > 0 {
+        println!("    ... {} lines above", from);
+    }
+    let column = (width().saturating_sub(16) / 2).max(10);
+    let unified_width = width().saturating_sub(12).max(10);
+    if style == DiffStyle::SideBySide {
+        println!(
+            "    {:>5}  {} | {}",
+            "",
+            format!("{:<column$}", "expected").bold(),
+            "actual".bold()
+        );
+    }
+    for line in from..to {
+        let e = expected.get(line).copied();
+        let a = actual.get(line).copied();
+        let token = (line == mismatch.line).then_some(mismatch.token);
+        let note = match (e, a) {
+            (Some(e), Some(a)) if only_whitespace_differs(e, a) => " (whitespace only)",
+            _ => "",
+        };
+        let same = e == a;
+        match style {
+            DiffStyle::SideBySide => {
+                let marker = if same { ' ' } else { '!' };
+                println!(
+                    "    {:>5}{} {} | {}{}",
+                    line + 1,
+                    marker,
+                    render(e.unwrap_or_default(), token, column, true),
+                    render(a.unwrap_or_default(), token, column, true),
+                    note
+                );
+            }
+            DiffStyle::Unified => {
+                if same {
+                    let text = render(e.unwrap_or_default(), None, unified_width, false);
+                    println!("    {:>5}   {}", line + 1, text);
+                    continue;
+                }
+                if let Some(e) = e {
+                    println!(
+                        "    {:>5} {} {}",
+                        line + 1,
+                        "-".red(),
+                        render(e, token, unified_width, false)
+                    );
+                }
+                if let Some(a) = a {
+                    let text = render(a, token, unified_width, false);
+                    println!("    {:>5} {} {}{}", line + 1, "+".green(), text, note);
+                }
+            }
+        }
+    }
+    if to < total {
+        println!("    ... {} lines below", total - to);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe, first_mismatch, token_span, whitespace_note, window};
+
+    #[test]
+    fn test_first_mismatch() {
+        let mismatch = first_mismatch("1 2\n3 4\n", "1 2\n3 5\n").unwrap();
+        assert_eq!((mismatch.line, mismatch.token), (1, 1));
+        let mismatch = first_mismatch("1\n2\n", "1\n").unwrap();
+        assert_eq!((mismatch.line, mismatch.token), (1, 0));
+        assert!(first_mismatch("1  2 \n", "1 2").is_none());
+    }
+
+    #[test]
+    fn test_token_span() {
+        assert_eq!(token_span("ab  cd e", 0), Some((0, 2)));
+        assert_eq!(token_span("ab  cd e", 1), Some((4, 6)));
+        assert_eq!(token_span("ab  cd e", 2), Some((7, 8)));
+        assert_eq!(token_span("ab  cd e", 3), None);
+        assert_eq!(token_span("  x", 0), Some((2, 3)));
+    }
+
+    #[test]
+    fn test_window() {
+        assert_eq!(
+            window("1 2 3", Some((2, 3)), 10),
+            ("1 ".to_string(), "2".to_string(), " 3".to_string())
+        );
+        // A mismatch far into one long line is shown, with both ends cut.
+        let line = (0..1000)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let start = line.find(" 500 ").unwrap() + 1;
+        let (before, within, after) = window(&line, Some((start, start + 3)), 21);
+        assert_eq!(within, "500");
+        assert_eq!(before, "…498 499 ");
+        assert_eq!(after, " 501 502…");
+        // Without a mismatching token the line is cut from its start.
+        let (before, within, after) = window(&line, None, 10);
+        assert_eq!((before.as_str(), within.as_str()), ("", ""));
+        assert_eq!(after, "0 1 2 3 …");
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            describe("YES\n3\n", "YES\n4\n"),
+            "line 2, token 1: expected '3', found '4'"
+        );
+        assert_eq!(
+            describe("1 2\n", "1\n"),
+            "line 1, token 2: expected '2', found nothing"
+        );
+    }
+
+    #[test]
+    fn test_whitespace_note() {
+        assert_eq!(whitespace_note("1\n", "1\n"), None);
+        assert_eq!(
+            whitespace_note("1\n2\n", "1\r\n2\r\n").unwrap(),
+            "note: output uses CRLF line endings, expected answer uses LF"
+        );
+        assert_eq!(
+            whitespace_note("1 2\n", "1 2 \n\n").unwrap(),
+            "note: output differs from the answer only in trailing whitespace"
+        );
+        assert_eq!(
+            whitespace_note("1 2\n", "1  2\n").unwrap(),
+            "note: output differs from the answer only in whitespace"
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use crate::runner::{TestResult, Verdict};
+use crossterm::execute;
+use crossterm::style::{Color, ResetColor, SetForegroundColor};
+use rust_competitive_helper_util::TestGroup;
+use std::collections::HashMap;
+
+pub(crate) struct GroupResult {
+    pub name: String,
+    pub points: u64,
+    /// Why the group scores nothing, or `None` when it passed.
+    pub failure: Option<String>,
+}
+
+impl GroupResult {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Test names of a group, with `a-b` ranges expanded.
+fn test_names(group: &TestGroup) -> Vec<String> {
+    let mut names = Vec::new();
+    for entry in &group.tests {
+        let range = entry
+            .split_once('-')
+            .and_then(|(from, to)| Some((from.parse::<u64>().ok()?, to.parse::<u64>().ok()?)));
+        match range {
+            Some((from, to)) => names.extend((from..=to).map(|id| id.to_string())),
+            None => names.push(entry.clone()),
+        }
+    }
+    names
+}
+
+/// Why the tests of `group` alone don't earn its points, if they don't.
+fn own_failure(group: &TestGroup, results: &HashMap<&str, &TestResult>) -> Option<String> {
+    for name in test_names(group) {
+        let Some(result) = results.get(name.as_str()) else {
+            return Some(format!("test {} not run", name));
+        };
+        if result.verdict == Verdict::Unknown {
+            return Some(format!("test {} has no expected output", name));
+        }
+        if !result.passed() {
+            return Some(format!("test {}: {}", name, result.verdict.short()));
+        }
+    }
+    None
+}
+
+fn failure(
+    name: &str,
+    groups: &HashMap<&str, &TestGroup>,
+    results: &HashMap<&str, &TestResult>,
+    memo: &mut HashMap<String, Option<Option<String>>>,
+) -> Option<String> {
+    match memo.get(name) {
+        Some(Some(failure)) => return failure.clone(),
+        Some(None) => return Some("dependency cycle".to_string()),
+        None => {}
+    }
+    let Some(group) = groups.get(name) else {
+        return Some(format!("unknown group {}", name));
+    };
+    // Marks the group as being evaluated, to detect cycles.
+    memo.insert(name.to_string(), None);
+    let failure = own_failure(group, results).or_else(|| {
+        group.dependencies.iter().find_map(|dependency| {
+            failure(dependency, groups, results, memo).map(|_| format!("depends on {}", dependency))
+        })
+    });
+    memo.insert(name.to_string(), Some(failure.clone()));
+    failure
+}
+
+/// Scores every group: a group passes when all of its tests pass and every
+/// group it depends on passes too.
+pub(crate) fn evaluate(groups: &[TestGroup], results: &[TestResult]) -> Vec<GroupResult> {
+    let by_name = groups
+        .iter()
+        .map(|group| (group.name.as_str(), group))
+        .collect::<HashMap<_, _>>();
+    let results = results
+        .iter()
+        .map(|result| (result.name.as_str(), result))
+        .collect::<HashMap<_, _>>();
+    let mut memo = HashMap::new();
+    groups
+        .iter()
+        .map(|group| GroupResult {
+            name: group.name.clone(),
+            points: group.points,
+            failure: failure(&group.name, &by_name, &results, &mut memo),
+        })
+        .collect()
+}
+
+pub(crate) fn score(groups: &[GroupResult]) -> (u64, u64) {
+    let total = groups.iter().map(|group| group.points).sum();
+    let scored = groups
+        .iter()
+        .filter(|group| group.passed())
+        .map(|group| group.points)
+        .sum();
+    (scored, total)
+}
+
+pub(crate) fn print(groups: &[GroupResult]) {
+    let mut stdout = std::io::stdout();
+    let width = groups
+        .iter()
+        .map(|group| group.name.len())
+        .max()
+        .unwrap_or_default();
+    for group in groups {
+        let (color, earned) = if group.passed() {
+            (Color::Green, group.points)
+        } else {
+            (Color::Red, 0)
+        };
+        print!("Group {:<width$} ", group.name);
+        let _ = execute!(stdout, SetForegroundColor(color));
+        print!("{:>4}/{:<4}", earned, group.points);
+        let _ = execute!(stdout, ResetColor);
+        match &group.failure {
+            Some(failure) => println!(" {}", failure),
+            None => println!(),
+        }
+    }
+    let (scored, total) = score(groups);
+    println!("Score {}/{}", scored, total);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate, score, test_names};
+    use crate::runner::{TestResult, Verdict};
+    use rust_competitive_helper_util::TestGroup;
+    use std::time::Duration;
+
+    fn result(name: &str, verdict: Verdict) -> TestResult {
+        TestResult {
+            name: name.to_string(),
+            verdict,
+            time: Duration::ZERO,
+            judge_time: None,
+            memory: None,
+            message: None,
+            outputs: None,
+            stderr: String::new(),
+            expected: None,
+        }
+    }
+
+    fn group(name: &str, points: u64, tests: &[&str], dependencies: &[&str]) -> TestGroup {
+        TestGroup {
+            name: name.to_string(),
+            points,
+            tests: tests.iter().map(|test| test.to_string()).collect(),
+            dependencies: dependencies.iter().map(|dep| dep.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(
+            test_names(&group("a", 1, &["1-3", "big", "7"], &[])),
+            vec!["1", "2", "3", "big", "7"]
+        );
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let results = [
+            result("1", Verdict::Ok),
+            result("2", Verdict::Ok),
+            result("3", Verdict::TimeLimit),
+            result("4", Verdict::Ok),
+        ];
+        let groups = [
+            group("small", 20, &["1-2"], &[]),
+            group("medium", 30, &["3"], &["small"]),
+            group("large", 50, &["4"], &["medium"]),
+            group("extra", 10, &["5"], &[]),
+        ];
+        let evaluated = evaluate(&groups, &results);
+        let failures = evaluated
+            .iter()
+            .map(|group| group.failure.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            failures,
+            vec![
+                None,
+                Some("test 3: TL"),
+                Some("depends on medium"),
+                Some("test 5 not run"),
+            ]
+        );
+        assert_eq!(score(&evaluated), (20, 110));
+    }
+
+    #[test]
+    fn test_dependency_cycle() {
+        let results = [result("1", Verdict::Ok)];
+        let groups = [group("a", 1, &["1"], &["b"]), group("b", 1, &["1"], &["a"])];
+        assert!(evaluate(&groups, &results)
+            .iter()
+            .all(|group| !group.passed()));
+    }
+}
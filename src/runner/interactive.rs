@@ -0,0 +1,247 @@
+use crate::runner::{
+    build, load_task_or_fail, outputs_match, read_to_end, task_dir, wait_with_limit, Profile, Run,
+    Solution, TRANSCRIPT_EXTENSION,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+/// One exchange of an interactive run: a line printed by the solution and the
+/// line the interactor answered with. Either side may be missing, e.g. the
+/// initial data sent before the first query, or the final answer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Exchange {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub query: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub response: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Transcript {
+    pub exchanges: Vec<Exchange>,
+}
+
+enum Event {
+    Query(String),
+    Response(String),
+}
+
+/// What the terminal loop waits for: a typed line, or the end of either the
+/// terminal input or the solution's output.
+enum Input {
+    Line(String),
+    Done,
+}
+
+fn pair_events(events: Vec<Event>) -> Vec<Exchange> {
+    let mut exchanges = Vec::new();
+    let mut pending: Option<String> = None;
+    for event in events {
+        match event {
+            Event::Query(query) => {
+                if let Some(query) = pending.replace(query) {
+                    exchanges.push(Exchange {
+                        query: Some(query),
+                        response: None,
+                    });
+                }
+            }
+            Event::Response(response) => exchanges.push(Exchange {
+                query: pending.take(),
+                response: Some(response),
+            }),
+        }
+    }
+    if let Some(query) = pending {
+        exchanges.push(Exchange {
+            query: Some(query),
+            response: None,
+        });
+    }
+    exchanges
+}
+
+fn next_transcript_path(task_name: &str) -> String {
+    let dir = format!("{}/tests", task_dir(task_name));
+    let mut id = 1;
+    while Path::new(&format!("{}/{}{}", dir, id, TRANSCRIPT_EXTENSION)).exists() {
+        id += 1;
+    }
+    format!("{}/{}{}", dir, id, TRANSCRIPT_EXTENSION)
+}
+
+/// Runs the task with the terminal acting as the interactor. Every line the
+/// solution prints is a query, every line typed in is a response. When `save`
+/// is set the exchanges are stored in `tests/` for later replay.
+pub fn interact(task_name: &str, save: bool) -> Result<(), String> {
+    load_task_or_fail(task_name)?;
+    let binary = build(task_name, Profile::Release)?;
+    let mut child = Command::new(&binary)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|err| format!("Couldn't run {}: {}", binary.display(), err))?;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let (sender, receiver) = mpsc::channel();
+    let stdout = child.stdout.take().unwrap();
+    let reader = {
+        let events = events.clone();
+        let sender = sender.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                println!("> {}", line);
+                events.lock().unwrap().push(Event::Query(line));
+            }
+            let _ = sender.send(Input::Done);
+        })
+    };
+    // Not joined: it stays blocked on the terminal once the solution is done.
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+            if sender.send(Input::Line(line)).is_err() {
+                return;
+            }
+        }
+        let _ = sender.send(Input::Done);
+    });
+    let mut stdin = child.stdin.take().unwrap();
+    while let Ok(Input::Line(line)) = receiver.recv() {
+        events.lock().unwrap().push(Event::Response(line.clone()));
+        if writeln!(stdin, "{}", line)
+            .and_then(|_| stdin.flush())
+            .is_err()
+        {
+            break;
+        }
+    }
+    drop(stdin);
+    let status = child.wait().map_err(|err| err.to_string())?;
+    let _ = reader.join();
+    println!("Solution finished with {}", status);
+    let events = std::mem::take(&mut *events.lock().unwrap());
+    let transcript = Transcript {
+        exchanges: pair_events(events),
+    };
+    if save && !transcript.exchanges.is_empty() {
+        let path = next_transcript_path(task_name);
+        std::fs::write(&path, serde_json::to_string_pretty(&transcript).unwrap())
+            .map_err(|err| format!("Can't write {}: {}", path, err))?;
+        println!("Transcript saved to {}", path);
+    }
+    Ok(())
+}
+
+/// Plays the interactor's side of `transcript`: checks each query the
+/// solution prints and answers with the recorded response.
+fn play(transcript: Transcript, mut stdin: ChildStdin, stdout: ChildStdout) -> Result<(), String> {
+    let mut lines = BufReader::new(stdout).lines().map_while(Result::ok);
+    for (i, exchange) in transcript.exchanges.into_iter().enumerate() {
+        if let Some(expected) = exchange.query {
+            match lines.next() {
+                None => {
+                    return Err(format!(
+                        "exchange {}: expected query '{}', got end of output",
+                        i + 1,
+                        expected
+                    ))
+                }
+                Some(actual) if !outputs_match(&expected, &actual) => {
+                    return Err(format!(
+                        "exchange {}: expected query '{}', got '{}'",
+                        i + 1,
+                        expected,
+                        actual
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+        if let Some(response) = exchange.response {
+            writeln!(stdin, "{}", response)
+                .and_then(|_| stdin.flush())
+                .map_err(|_| format!("exchange {}: solution closed its input", i + 1))?;
+        }
+    }
+    drop(stdin);
+    match lines.find(|line| !line.trim().is_empty()) {
+        None => Ok(()),
+        Some(extra) => Err(format!(
+            "unexpected output after transcript end: '{}'",
+            extra
+        )),
+    }
+}
+
+pub(crate) fn replay(solution: &Solution, path: &Path, limit: Duration) -> Result<Run, String> {
+    let transcript: Transcript = std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|err| err.to_string()))
+        .map_err(|err| format!("Can't read transcript {}: {}", path.display(), err))?;
+    let mut child = solution.spawn(None)?;
+    let stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let stderr = read_to_end(child.stderr.take().unwrap());
+    let interactor = std::thread::spawn(move || play(transcript, stdin, stdout));
+    let execution = wait_with_limit(&mut child, limit);
+    let stderr = stderr.join().unwrap_or_default();
+    let checked = interactor
+        .join()
+        .unwrap_or_else(|_| Err("interactor panicked".to_string()));
+    Ok(Run {
+        execution,
+        checked,
+        outputs: None,
+        stderr,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pair_events, Event, Exchange, Transcript};
+
+    fn exchange(query: Option<&str>, response: Option<&str>) -> Exchange {
+        Exchange {
+            query: query.map(str::to_string),
+            response: response.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_pair_events() {
+        let events = vec![
+            Event::Response("5".to_string()),
+            Event::Query("? 1".to_string()),
+            Event::Response("<".to_string()),
+            Event::Query("debug".to_string()),
+            Event::Query("? 3".to_string()),
+            Event::Response(">".to_string()),
+            Event::Query("! 2".to_string()),
+        ];
+        assert_eq!(
+            pair_events(events),
+            vec![
+                exchange(None, Some("5")),
+                exchange(Some("? 1"), Some("<")),
+                exchange(Some("debug"), None),
+                exchange(Some("? 3"), Some(">")),
+                exchange(Some("! 2"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transcript_roundtrip() {
+        let transcript = Transcript {
+            exchanges: vec![exchange(None, Some("5")), exchange(Some("! 2"), None)],
+        };
+        let json = serde_json::to_string(&transcript).unwrap();
+        assert_eq!(json, r#"{"exchanges":[{"response":"5"},{"query":"! 2"}]}"#);
+        let parsed: Transcript = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.exchanges, transcript.exchanges);
+    }
+}
@@ -0,0 +1,124 @@
+use regex_syntax::hir::{Class, Hir, HirKind};
+use rust_competitive_helper_util::{IOEnum, IOType, Task};
+
+/// Files a solution reads its input from and writes its output to instead
+/// of the standard streams, as the judge sets them up.
+#[derive(Default)]
+pub(crate) struct IoFiles {
+    pub input: Option<String>,
+    pub output: Option<String>,
+}
+
+impl IoFiles {
+    pub(crate) fn new(task: &Task) -> Result<Self, String> {
+        Ok(Self {
+            input: file_name(&task.input)?,
+            output: file_name(&task.output)?,
+        })
+    }
+
+    /// Whether the solution only uses the standard streams, so it can run
+    /// in place without a scratch directory.
+    pub(crate) fn is_stdio(&self) -> bool {
+        self.input.is_none() && self.output.is_none()
+    }
+}
+
+fn file_name(io: &IOType) -> Result<Option<String>, String> {
+    match io.io_type {
+        IOEnum::StdIn | IOEnum::StdOut => Ok(None),
+        IOEnum::File => io
+            .file_name
+            .clone()
+            .map(Some)
+            .ok_or_else(|| "task.json: file IO without a fileName".to_string()),
+        IOEnum::Regex => {
+            let pattern = io
+                .pattern
+                .as_deref()
+                .ok_or_else(|| "task.json: regex input without a pattern".to_string())?;
+            matching_file_name(pattern).map(Some)
+        }
+    }
+}
+
+/// Characters tried first when a class leaves a choice, so generated names
+/// stay plain.
+const PREFERRED: &str = "abcdefghijklmnopqrstuvwxyz0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_-";
+
+fn pick(class: &Class) -> Option<char> {
+    let contains = |c: char| match class {
+        Class::Unicode(class) => class
+            .ranges()
+            .iter()
+            .any(|range| range.start() <= c && c <= range.end()),
+        Class::Bytes(class) => class
+            .ranges()
+            .iter()
+            .any(|range| range.start() as char <= c && c <= range.end() as char),
+    };
+    PREFERRED.chars().find(|&c| contains(c)).or(match class {
+        Class::Unicode(class) => class.ranges().first().map(|range| range.start()),
+        Class::Bytes(class) => class.ranges().first().map(|range| range.start() as char),
+    })
+}
+
+/// Appends the shortest plain string matching `hir` to `res`.
+fn sample(hir: &Hir, res: &mut String) -> Result<(), String> {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => {}
+        HirKind::Literal(literal) => res.push_str(&String::from_utf8_lossy(&literal.0)),
+        HirKind::Class(class) => res.push(pick(class).ok_or("empty character class")?),
+        HirKind::Repetition(repetition) => {
+            for _ in 0..repetition.min {
+                sample(&repetition.sub, res)?;
+            }
+        }
+        HirKind::Capture(capture) => sample(&capture.sub, res)?,
+        HirKind::Concat(parts) => {
+            for part in parts {
+                sample(part, res)?;
+            }
+        }
+        HirKind::Alternation(alternatives) => sample(&alternatives[0], res)?,
+    }
+    Ok(())
+}
+
+/// A file name matching the input `pattern` of a regex-input task, such as
+/// `input.txt` for `.*input\.txt` or `a.in` for `.+\.in`.
+pub(crate) fn matching_file_name(pattern: &str) -> Result<String, String> {
+    let invalid = |err: String| format!("Invalid input pattern {}: {}", pattern, err);
+    let hir = regex_syntax::parse(pattern).map_err(|err| invalid(err.to_string()))?;
+    let mut name = String::new();
+    sample(&hir, &mut name).map_err(|err| invalid(err.to_string()))?;
+    if name.is_empty() || name.contains(['/', '\\', '\0']) {
+        return Err(invalid(format!("no usable file name, got '{}'", name)));
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matching_file_name;
+    use regex::Regex;
+
+    #[test]
+    fn test_matching_file_name() {
+        for pattern in [
+            r"input\.txt",
+            r".*input\.txt",
+            r"[a-z_]+_(input|in)\.txt",
+            r"^cutting_cake_.*\.in$",
+            r"\w+-\d{2}\.txt",
+        ] {
+            let name = matching_file_name(pattern).unwrap();
+            let full = Regex::new(&format!("^(?:{})$", pattern)).unwrap();
+            assert!(full.is_match(&name), "{} doesn't match {}", name, pattern);
+        }
+        assert_eq!(matching_file_name(r"input\.txt").unwrap(), "input.txt");
+        assert_eq!(matching_file_name(r".*input\.txt").unwrap(), "input.txt");
+        assert_eq!(matching_file_name(r".+\.in").unwrap(), "a.in");
+        assert!(matching_file_name(".*").is_err());
+    }
+}
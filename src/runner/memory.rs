@@ -0,0 +1,44 @@
+use std::process::{Child, ExitStatus};
+
+/// Non-blocking wait that also reports the peak resident set size of the
+/// child in kilobytes. Memory is only measured on Linux, through `wait4`.
+#[cfg(target_os = "linux")]
+pub(crate) fn try_wait(child: &mut Child) -> Option<(ExitStatus, Option<u64>)> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut status = 0;
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    let pid = unsafe {
+        libc::wait4(
+            child.id() as libc::pid_t,
+            &mut status,
+            libc::WNOHANG,
+            &mut usage,
+        )
+    };
+    if pid <= 0 {
+        return None;
+    }
+    Some((ExitStatus::from_raw(status), Some(usage.ru_maxrss as u64)))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn try_wait(child: &mut Child) -> Option<(ExitStatus, Option<u64>)> {
+    child.try_wait().ok().flatten().map(|status| (status, None))
+}
+
+pub(crate) fn format_memory(kilobytes: u64) -> String {
+    format!("{:.1} MB", kilobytes as f64 / 1024.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_memory;
+
+    #[test]
+    fn test_format_memory() {
+        assert_eq!(format_memory(0), "0.0 MB");
+        assert_eq!(format_memory(1536), "1.5 MB");
+        assert_eq!(format_memory(262144), "256.0 MB");
+    }
+}
@@ -0,0 +1,895 @@
+mod adhoc;
+mod affinity;
+mod answers;
+mod calibrate;
+mod diff;
+mod groups;
+mod interactive;
+mod io;
+mod memory;
+mod panic;
+mod report;
+mod verify;
+mod watch;
+
+use crate::config::{Config, DiffStyle};
+use crate::submit::site_of;
+use crossterm::execute;
+use crossterm::style::{Color, ResetColor, SetForegroundColor};
+use io::IoFiles;
+use rust_competitive_helper_util::build::TASK_ENV;
+use rust_competitive_helper_util::{load_task, Task, Test};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+pub(crate) use verify::ScratchDir;
+
+pub use adhoc::{run, InputSource, RunOptions};
+pub use calibrate::{calibrate, BENCHMARK};
+pub use interactive::interact;
+pub use verify::verify;
+pub use watch::watch;
+
+const TRANSCRIPT_EXTENSION: &str = ".transcript";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verdict {
+    Ok,
+    WrongAnswer,
+    RuntimeError,
+    TimeLimit,
+    MemoryLimit,
+    /// The test has no expected output to check against.
+    Unknown,
+}
+
+impl Verdict {
+    /// Verdicts a test can be expected to get.
+    const EXPECTABLE: [Verdict; 5] = [
+        Verdict::Ok,
+        Verdict::WrongAnswer,
+        Verdict::RuntimeError,
+        Verdict::TimeLimit,
+        Verdict::MemoryLimit,
+    ];
+
+    fn parse(short: &str) -> Option<Verdict> {
+        Self::EXPECTABLE
+            .into_iter()
+            .find(|verdict| verdict.short().eq_ignore_ascii_case(short))
+    }
+
+    fn short(&self) -> &'static str {
+        match self {
+            Verdict::Ok => "OK",
+            Verdict::WrongAnswer => "WA",
+            Verdict::RuntimeError => "RE",
+            Verdict::TimeLimit => "TL",
+            Verdict::MemoryLimit => "ML",
+            Verdict::Unknown => "?",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Verdict::Ok => Color::Green,
+            Verdict::TimeLimit | Verdict::MemoryLimit | Verdict::Unknown => Color::Yellow,
+            Verdict::WrongAnswer | Verdict::RuntimeError => Color::Red,
+        }
+    }
+}
+
+pub struct TestResult {
+    pub name: String,
+    pub verdict: Verdict,
+    pub time: Duration,
+    /// `time` scaled to the judge, when the site has been calibrated.
+    pub judge_time: Option<Duration>,
+    /// Peak resident set size in kilobytes, when it could be measured.
+    pub memory: Option<u64>,
+    pub message: Option<String>,
+    /// Expected and actual output of a wrong answer, shown as a diff.
+    pub(crate) outputs: Option<diff::Outputs>,
+    pub stderr: String,
+    /// Verdict the test is meant to get, from `expectedVerdict` in task.json.
+    pub expected: Option<Verdict>,
+}
+
+impl TestResult {
+    /// Whether the test got the verdict it should. Tests without an expected
+    /// output don't count as failures.
+    pub fn passed(&self) -> bool {
+        self.verdict == self.expected.unwrap_or(Verdict::Ok) || self.verdict == Verdict::Unknown
+    }
+
+    fn color(&self) -> Color {
+        if self.verdict == Verdict::Unknown {
+            self.verdict.color()
+        } else if self.passed() {
+            Color::Green
+        } else if self.verdict == Verdict::Ok {
+            Color::Red
+        } else {
+            self.verdict.color()
+        }
+    }
+}
+
+enum TestKind {
+    Classic { input: PathBuf, output: PathBuf },
+    Transcript(PathBuf),
+}
+
+struct TestCase {
+    name: String,
+    kind: TestKind,
+    expected: Option<Verdict>,
+}
+
+pub(crate) struct Limits {
+    /// Local time limit, i.e. the task's limit adjusted by `time_multiplier`.
+    pub time: Duration,
+    /// Memory limit in kilobytes.
+    pub memory: Option<u64>,
+    /// How much slower the judge is than this machine, from `calibrate`.
+    pub time_multiplier: Option<f64>,
+}
+
+pub(crate) struct Execution {
+    status: Option<ExitStatus>,
+    time: Duration,
+    memory: Option<u64>,
+}
+
+/// What a single run produced: the execution itself and the result of
+/// checking its output.
+pub(crate) struct Run {
+    execution: Execution,
+    checked: Result<(), String>,
+    /// Expected and actual output of classic tests.
+    outputs: Option<diff::Outputs>,
+    stderr: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Profile {
+    Release,
+    /// Unoptimized build with overflow checks and debug assertions forced on.
+    Debug,
+}
+
+/// How many tests run at once, and whether each worker's runs are pinned to
+/// a CPU of their own.
+#[derive(Clone, Copy)]
+pub(crate) struct Parallelism {
+    pub jobs: usize,
+    pub pin: bool,
+}
+
+impl Parallelism {
+    pub(crate) fn from_config(config: &Config, jobs: Option<usize>, pin: bool) -> Self {
+        Self {
+            jobs: jobs.unwrap_or(config.test_jobs).max(1),
+            pin: pin || config.pin_cpus,
+        }
+    }
+}
+
+/// A built solution together with the files it does its IO through.
+pub(crate) struct Program {
+    pub binary: PathBuf,
+    pub io: IoFiles,
+}
+
+impl Program {
+    pub(crate) fn new(binary: PathBuf, task: &Task) -> Result<Self, String> {
+        Ok(Self {
+            binary,
+            io: IoFiles::new(task)?,
+        })
+    }
+}
+
+/// The solution as run by one worker.
+pub(crate) struct Solution<'a> {
+    pub program: &'a Program,
+    pub cpu: Option<usize>,
+}
+
+impl Solution<'_> {
+    /// Starts the solution, in `dir` when given.
+    pub(crate) fn spawn(&self, dir: Option<&Path>) -> Result<Child, String> {
+        let binary = &self.program.binary;
+        let mut command = match dir {
+            Some(dir) => {
+                let binary = binary
+                    .canonicalize()
+                    .map_err(|err| format!("Can't find {}: {}", binary.display(), err))?;
+                let mut command = Command::new(binary);
+                command.current_dir(dir);
+                command
+            }
+            None => Command::new(binary),
+        };
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(cpu) = self.cpu {
+            affinity::pin(&mut command, cpu);
+        }
+        command.spawn().map_err(|err| err.to_string())
+    }
+}
+
+#[derive(Default)]
+pub struct TestOptions {
+    /// Run the tests on a debug build with overflow checks before the
+    /// release build.
+    pub double_run: bool,
+    /// Write `report.json` and a JUnit `report.xml` into the task directory.
+    pub report: bool,
+    /// Number of tests to run in parallel, overriding `test_jobs` in config.
+    pub jobs: Option<usize>,
+    /// Pin every worker to its own CPU.
+    pub pin: bool,
+    /// Only run tests whose source or tags in task.json include this tag.
+    pub only: Option<String>,
+    /// Store the output of tests without an expected output without asking.
+    pub accept: bool,
+}
+
+pub(crate) fn task_dir(task_name: &str) -> String {
+    format!("tasks/{}", task_name)
+}
+
+pub(crate) fn load_task_or_fail(task_name: &str) -> Result<Task, String> {
+    load_task(task_dir(task_name)).ok_or_else(|| format!("Task not found: {}", task_dir(task_name)))
+}
+
+/// Whether `main` was bundled from `task`, going by their names and URLs.
+pub(crate) fn same_task(main: &Task, task: &Task) -> bool {
+    main.name == task.name && main.url == task.url
+}
+
+/// The value of `TASK_ENV` for building `task_name`. Cargo only reruns the
+/// task's build script when its sources or this value change, so while
+/// `main/` holds some other task it gets a value that was never used before.
+fn task_env(task_name: &str, task: &Task, main: Option<&Task>) -> String {
+    if main.is_some_and(|main| same_task(main, task)) {
+        return task_name.to_string();
+    }
+    static BUILDS: AtomicUsize = AtomicUsize::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!(
+        "{} {}-{}",
+        task_name,
+        nanos,
+        BUILDS.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Fails if `main/task.json` is there but belongs to another task than the
+/// one just built.
+fn check_bundled(task_name: &str, task: &Task, main: Option<&Task>) -> Result<(), String> {
+    match main {
+        Some(main) if !same_task(main, task) => Err(format!(
+            "main/task.json is still {} ({}) after building {}, the bundle wasn't regenerated. \
+             Make sure tasks/{}/build.rs uses an up to date rust-competitive-helper-util.",
+            main.name, main.url, task_name, task_name
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Builds the task crate (which regenerates `main/src/main.rs` through its
+/// build script) and then the `main` crate, returning the solution binary.
+pub(crate) fn build(task_name: &str, profile: Profile) -> Result<PathBuf, String> {
+    let task = load_task_or_fail(task_name)?;
+    let task_env = task_env(task_name, &task, load_task("main").as_ref());
+    for package in [task_name, "main"] {
+        let mut command = Command::new("cargo");
+        command
+            .args(["build", "--package", package])
+            .env(TASK_ENV, &task_env);
+        match profile {
+            Profile::Release => {
+                command.arg("--release");
+            }
+            Profile::Debug => {
+                command
+                    .env("CARGO_PROFILE_DEV_OVERFLOW_CHECKS", "true")
+                    .env("CARGO_PROFILE_DEV_DEBUG_ASSERTIONS", "true");
+            }
+        }
+        let status = command
+            .status()
+            .map_err(|err| format!("Couldn't run cargo: {}", err))?;
+        if !status.success() {
+            return Err(format!("Build of {} failed", package));
+        }
+    }
+    check_bundled(task_name, &task, load_task("main").as_ref())?;
+    let dir = match profile {
+        Profile::Release => "release",
+        Profile::Debug => "debug",
+    };
+    Ok(PathBuf::from(format!(
+        "target/{}/main{}",
+        dir,
+        std::env::consts::EXE_SUFFIX
+    )))
+}
+
+fn test_sort_key(name: &str) -> (u64, String) {
+    (name.parse().unwrap_or(u64::MAX), name.to_string())
+}
+
+fn collect_tests(config: &Config, task_name: &str) -> Vec<TestCase> {
+    let dir = format!("{}/tests", task_dir(task_name));
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut tests = Vec::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if let Some(name) = file_name.strip_suffix(&config.input_file_extension) {
+            tests.push(TestCase {
+                name: name.to_string(),
+                kind: TestKind::Classic {
+                    input: entry.path(),
+                    output: Path::new(&dir)
+                        .join(format!("{}{}", name, config.output_file_extension)),
+                },
+                expected: None,
+            });
+        } else if let Some(name) = file_name.strip_suffix(TRANSCRIPT_EXTENSION) {
+            tests.push(TestCase {
+                name: name.to_string(),
+                kind: TestKind::Transcript(entry.path()),
+                expected: None,
+            });
+        }
+    }
+    tests.sort_by_key(|test| test_sort_key(&test.name));
+    tests
+}
+
+/// Waits for `child` to finish, killing it once `limit` has passed.
+pub(crate) fn wait_with_limit(child: &mut Child, limit: Duration) -> Execution {
+    let start = Instant::now();
+    loop {
+        if let Some((status, memory)) = memory::try_wait(child) {
+            return Execution {
+                status: Some(status),
+                time: start.elapsed(),
+                memory,
+            };
+        }
+        if start.elapsed() > limit {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Execution {
+                status: None,
+                time: start.elapsed(),
+                memory: None,
+            };
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+pub(crate) fn read_to_end<R: Read + Send + 'static>(mut source: R) -> JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut content = String::new();
+        let _ = source.read_to_string(&mut content);
+        content
+    })
+}
+
+/// Outputs match when they consist of the same whitespace-separated tokens.
+pub(crate) fn outputs_match(expected: &str, actual: &str) -> bool {
+    expected.split_whitespace().eq(actual.split_whitespace())
+}
+
+/// Runs a classic test. Tasks with file IO run in a scratch directory with
+/// the input placed under the file name the task reads from.
+fn run_classic(
+    solution: &Solution,
+    input: &Path,
+    output: &Path,
+    limit: Duration,
+) -> Result<Run, String> {
+    let input = std::fs::read(input).unwrap_or_default();
+    let expected = std::fs::read_to_string(output).unwrap_or_default();
+    let io = &solution.program.io;
+    let dir = if io.is_stdio() {
+        None
+    } else {
+        Some(ScratchDir::new("test")?)
+    };
+    let input = match (&dir, &io.input) {
+        (Some(dir), Some(name)) => {
+            std::fs::write(dir.0.join(name), &input)
+                .map_err(|err| format!("Can't write {}: {}", name, err))?;
+            Vec::new()
+        }
+        _ => input,
+    };
+    let mut child = solution.spawn(dir.as_ref().map(|dir| dir.0.as_path()))?;
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = std::thread::spawn(move || {
+        let _ = stdin.write_all(&input);
+    });
+    let stdout = read_to_end(child.stdout.take().unwrap());
+    let stderr = read_to_end(child.stderr.take().unwrap());
+    let execution = wait_with_limit(&mut child, limit);
+    let _ = writer.join();
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    let actual = match (&dir, &io.output) {
+        (Some(dir), Some(name)) => std::fs::read_to_string(dir.0.join(name)).unwrap_or_default(),
+        _ => stdout,
+    };
+    let checked = if expected.trim().is_empty() || outputs_match(&expected, &actual) {
+        Ok(())
+    } else {
+        Err(diff::describe(&expected, &actual))
+    };
+    Ok(Run {
+        execution,
+        checked,
+        outputs: Some(diff::Outputs { expected, actual }),
+        stderr,
+    })
+}
+
+/// Turns a run into a verdict. Limits are checked before the exit status and
+/// the output, the same order judges report them in.
+fn judge(name: &str, run: Result<Run, String>, limits: &Limits) -> TestResult {
+    let result = |verdict, execution: &Execution, message| TestResult {
+        name: name.to_string(),
+        verdict,
+        time: execution.time,
+        judge_time: limits
+            .time_multiplier
+            .map(|multiplier| execution.time.mul_f64(multiplier)),
+        memory: execution.memory,
+        message,
+        outputs: None,
+        stderr: String::new(),
+        expected: None,
+    };
+    let run = match run {
+        Ok(run) => run,
+        Err(err) => {
+            let execution = Execution {
+                status: None,
+                time: Duration::ZERO,
+                memory: None,
+            };
+            return result(Verdict::RuntimeError, &execution, Some(err));
+        }
+    };
+    let execution = &run.execution;
+    let stderr = run.stderr;
+    let result = |verdict, execution: &Execution, message| TestResult {
+        stderr: stderr.clone(),
+        ..result(verdict, execution, message)
+    };
+    let over_memory =
+        matches!((execution.memory, limits.memory), (Some(used), Some(limit)) if used > limit);
+    match execution.status {
+        None => result(Verdict::TimeLimit, execution, None),
+        Some(_) if over_memory => result(Verdict::MemoryLimit, execution, None),
+        Some(status) if !status.success() => {
+            let message = panic::parse_panic(&stderr)
+                .map(|panic| format!("panicked: {}", panic))
+                .unwrap_or_else(|| format!("exit status: {}", status));
+            result(Verdict::RuntimeError, execution, Some(message))
+        }
+        Some(_) => match run.checked {
+            Ok(())
+                if run
+                    .outputs
+                    .as_ref()
+                    .is_some_and(|outputs| outputs.expected.trim().is_empty()) =>
+            {
+                TestResult {
+                    outputs: run.outputs,
+                    ..result(
+                        Verdict::Unknown,
+                        execution,
+                        Some("no expected output".to_string()),
+                    )
+                }
+            }
+            Ok(()) => {
+                let note = run
+                    .outputs
+                    .as_ref()
+                    .and_then(|outputs| diff::whitespace_note(&outputs.expected, &outputs.actual));
+                result(Verdict::Ok, execution, note)
+            }
+            Err(message) => TestResult {
+                outputs: run.outputs,
+                ..result(Verdict::WrongAnswer, execution, Some(message))
+            },
+        },
+    }
+}
+
+fn run_test(solution: &Solution, test: &TestCase, limits: &Limits) -> TestResult {
+    let run = match &test.kind {
+        TestKind::Classic { input, output } => run_classic(solution, input, output, limits.time),
+        TestKind::Transcript(path) => interactive::replay(solution, path, limits.time),
+    };
+    TestResult {
+        expected: test.expected,
+        ..judge(&test.name, run, limits)
+    }
+}
+
+/// Prints the message, the captured stderr and, for wrong answers, the
+/// output diff of a test.
+fn print_details(result: &TestResult, style: DiffStyle) {
+    if let Some(message) = &result.message {
+        for line in message.lines() {
+            println!("    {}", line);
+        }
+    }
+    if !result.stderr.trim().is_empty() {
+        println!("    stderr:");
+        for line in result.stderr.lines() {
+            println!("    | {}", line);
+        }
+    }
+    match &result.outputs {
+        Some(outputs) if result.verdict == Verdict::Unknown => diff::print_output(&outputs.actual),
+        Some(outputs) => diff::print(outputs, style),
+        None => {}
+    }
+}
+
+fn print_result(result: &TestResult, style: DiffStyle) {
+    let mut stdout = std::io::stdout();
+    print!("Test {:<8} ", result.name);
+    let _ = execute!(stdout, SetForegroundColor(result.color()));
+    print!("{:<3}", result.verdict.short());
+    let _ = execute!(stdout, ResetColor);
+    print!(" {:>6} ms", result.time.as_millis());
+    match result.expected {
+        None | Some(Verdict::Ok) => {}
+        Some(_) if result.verdict == Verdict::Unknown => {}
+        Some(_) if result.passed() => print!(" (expected)"),
+        Some(expected) => print!(" (expected {})", expected.short()),
+    }
+    if let Some(judge_time) = result.judge_time {
+        print!(" (judge ~{} ms)", judge_time.as_millis());
+    }
+    match result.memory {
+        Some(memory) => println!(" {:>10}", memory::format_memory(memory)),
+        None => println!(),
+    }
+    print_details(result, style);
+}
+
+/// Metadata of test `name` in task.json. Entries without a name describe
+/// the test at their 1-based position.
+fn test_meta<'a>(task: &'a Task, name: &str) -> Option<&'a Test> {
+    test_index(task, name).map(|index| &task.tests[index])
+}
+
+fn test_index(task: &Task, name: &str) -> Option<usize> {
+    task.tests
+        .iter()
+        .enumerate()
+        .position(|(i, test)| match &test.name {
+            Some(test_name) => test_name == name,
+            None => (i + 1).to_string() == name,
+        })
+}
+
+/// Tests of the task that can be run locally: `.in`/`.out` pairs for
+/// classic tasks and transcripts for interactive ones. Tests disabled in
+/// task.json are left out, and with `only` so are tests without that tag.
+fn task_tests(config: &Config, task_name: &str, task: &Task, only: Option<&str>) -> Vec<TestCase> {
+    let mut disabled = Vec::new();
+    let tests = collect_tests(config, task_name)
+        .into_iter()
+        .filter(|test| matches!(test.kind, TestKind::Transcript(_)) == task.interactive)
+        .filter_map(|test| {
+            let meta = test_meta(task, &test.name);
+            if meta.is_some_and(|meta| !meta.enabled) {
+                disabled.push(test.name);
+                return None;
+            }
+            if let Some(tag) = only {
+                if !meta.is_some_and(|meta| meta.has_tag(tag)) {
+                    return None;
+                }
+            }
+            let expected = meta
+                .and_then(|meta| meta.expected_verdict.as_deref())
+                .and_then(|verdict| {
+                    let parsed = Verdict::parse(verdict);
+                    if parsed.is_none() {
+                        eprintln!("Test {}: unknown expected verdict {}", test.name, verdict);
+                    }
+                    parsed
+                });
+            Some(TestCase { expected, ..test })
+        })
+        .collect();
+    if !disabled.is_empty() {
+        println!("Skipping disabled tests: {}", disabled.join(", "));
+    }
+    tests
+}
+
+fn limits(config: &Config, task: &Task) -> Limits {
+    let time_multiplier = config
+        .site(&site_of(config, &task.url))
+        .time_multiplier
+        .filter(|multiplier| *multiplier > 0.0);
+    let time = Duration::from_millis(task.time_limit);
+    Limits {
+        time: time_multiplier.map_or(time, |multiplier| time.div_f64(multiplier)),
+        memory: task
+            .memory_limit
+            .or(config.memory_limit)
+            .map(|mb| mb * 1024),
+        time_multiplier,
+    }
+}
+
+fn all_passed(results: &[TestResult]) -> bool {
+    results.iter().all(TestResult::passed)
+}
+
+/// Runs `tests` on `parallelism.jobs` workers. Results are handed to
+/// `on_result` in test order as soon as all earlier tests have finished.
+fn run_all(
+    program: &Program,
+    tests: &[TestCase],
+    limits: &Limits,
+    parallelism: Parallelism,
+    mut on_result: impl FnMut(&TestResult),
+) -> Vec<TestResult> {
+    let next = AtomicUsize::new(0);
+    let cpus = affinity::allowed_cpus();
+    let mut slots: Vec<Option<TestResult>> = tests.iter().map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for worker in 0..parallelism.jobs.min(tests.len()) {
+            let sender = sender.clone();
+            let next = &next;
+            let cpus = &cpus;
+            scope.spawn(move || {
+                let solution = Solution {
+                    program,
+                    cpu: parallelism.pin.then(|| cpus[worker % cpus.len()]),
+                };
+                loop {
+                    let id = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(test) = tests.get(id) else {
+                        break;
+                    };
+                    if sender
+                        .send((id, run_test(&solution, test, limits)))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+        let mut reported = 0;
+        for (id, result) in receiver {
+            slots[id] = Some(result);
+            while let Some(Some(result)) = slots.get(reported) {
+                on_result(result);
+                reported += 1;
+            }
+        }
+    });
+    slots.into_iter().flatten().collect()
+}
+
+fn run_suite(
+    program: &Program,
+    tests: &[TestCase],
+    limits: &Limits,
+    parallelism: Parallelism,
+    style: DiffStyle,
+) -> Vec<TestResult> {
+    let results = run_all(program, tests, limits, parallelism, |result| {
+        print_result(result, style)
+    });
+    let unknown = results
+        .iter()
+        .filter(|result| result.verdict == Verdict::Unknown)
+        .count();
+    let passed = results.iter().filter(|result| result.passed()).count() - unknown;
+    if unknown > 0 {
+        println!(
+            "Passed {}/{}, {} without expected output",
+            passed,
+            tests.len(),
+            unknown
+        );
+    } else {
+        println!("Passed {}/{}", passed, tests.len());
+    }
+    results
+}
+
+/// Points out tests that only fail in the debug build: these panic on an
+/// overflow or a bounds check that the release build silently gets past.
+fn report_debug_only_failures(debug: &[TestResult], release: &[TestResult]) {
+    for (debug, release) in debug.iter().zip(release) {
+        if !debug.passed() && release.passed() {
+            crate::submit::failure(&format!(
+                "Test {} passes in release but fails in debug ({}): the judge may behave differently",
+                debug.name,
+                debug.message.as_deref().unwrap_or(debug.verdict.short()),
+            ));
+        }
+    }
+}
+
+/// Builds the task and runs every test in `tasks/<name>/tests`. Interactive
+/// tasks are checked against their recorded transcripts. Returns `true` when
+/// all tests pass.
+pub fn run_tests(task_name: &str, options: &TestOptions) -> Result<bool, String> {
+    let config = Config::load();
+    let task = load_task_or_fail(task_name)?;
+    let tests = task_tests(&config, task_name, &task, options.only.as_deref());
+    if tests.is_empty() {
+        return Err(match &options.only {
+            Some(tag) => format!("No tests tagged {} in {}", tag, task_dir(task_name)),
+            None => format!("No tests found in {}/tests", task_dir(task_name)),
+        });
+    }
+    let limits = limits(&config, &task);
+    let parallelism = Parallelism::from_config(&config, options.jobs, options.pin);
+    let debug = if options.double_run {
+        let program = Program::new(build(task_name, Profile::Debug)?, &task)?;
+        println!("Debug build (overflow checks on):");
+        // Unoptimized code is much slower, don't hold it to the time limit.
+        let limits = Limits {
+            time: limits.time * 10,
+            memory: limits.memory,
+            time_multiplier: None,
+        };
+        Some(run_suite(
+            &program,
+            &tests,
+            &limits,
+            parallelism,
+            config.diff_style,
+        ))
+    } else {
+        None
+    };
+    let program = Program::new(build(task_name, Profile::Release)?, &task)?;
+    if options.double_run {
+        println!("Release build:");
+    }
+    let release = run_suite(&program, &tests, &limits, parallelism, config.diff_style);
+    if let Some(debug) = &debug {
+        report_debug_only_failures(debug, &release);
+    }
+    let groups = groups::evaluate(&task.groups, &release);
+    if !groups.is_empty() {
+        groups::print(&groups);
+    }
+    answers::offer_to_store(&config, task_name, &release, options.accept)?;
+    if options.report {
+        let mut suites = Vec::new();
+        if let Some(debug) = &debug {
+            suites.push(report::Suite {
+                name: "debug",
+                results: debug,
+            });
+        }
+        suites.push(report::Suite {
+            name: "release",
+            results: &release,
+        });
+        report::write(task_name, &task.url, &suites, &groups)?;
+    }
+    Ok(all_passed(&release) && debug.as_deref().is_none_or(all_passed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_bundled, outputs_match, task_env, test_meta, test_sort_key, Verdict};
+    use rust_competitive_helper_util::Task;
+
+    #[test]
+    fn test_outputs_match_ignores_whitespace() {
+        assert!(outputs_match("1 2\n3\n", "1  2 3"));
+        assert!(outputs_match("", "\n"));
+        assert!(!outputs_match("1 2", "1 3"));
+        assert!(!outputs_match("1 2", "1 2 3"));
+    }
+
+    #[test]
+    fn test_sort_numeric_first() {
+        let mut names = vec!["10", "2", "a", "1"];
+        names.sort_by_key(|name| test_sort_key(name));
+        assert_eq!(names, vec!["1", "2", "10", "a"]);
+    }
+
+    fn task(name: &str, url: &str) -> Task {
+        serde_json::from_str(&format!(
+            r#"{{
+                "name": "{}", "group": "", "url": "{}", "interactive": false,
+                "timeLimit": 1000, "testType": "single", "tests": [],
+                "input": {{"type": "stdin"}}, "output": {{"type": "stdout"}}
+            }}"#,
+            name, url
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_task_switch() {
+        let a = task("A", "https://codeforces.com/contest/1/problem/A");
+        let b = task("B", "https://codeforces.com/contest/1/problem/B");
+        let manual = task("Manual", "");
+        // main/ already holds the task, the build script needn't run again.
+        assert_eq!(task_env("a", &a, Some(&a)), "a");
+        // Switching back to a task must rerun its build script every time.
+        let first = task_env("a", &a, Some(&b));
+        let second = task_env("a", &a, Some(&b));
+        assert!(first.starts_with("a ") && second.starts_with("a "));
+        assert_ne!(first, second);
+        assert_ne!(task_env("a", &a, None), "a");
+        assert_ne!(
+            task_env("manual", &manual, Some(&task("Other", ""))),
+            "manual"
+        );
+
+        assert!(check_bundled("a", &a, Some(&a)).is_ok());
+        assert!(check_bundled("a", &a, None).is_ok());
+        assert!(check_bundled("a", &a, Some(&b)).is_err());
+        // Manual tasks have no URL, so the name tells them apart.
+        assert!(check_bundled("manual", &manual, Some(&task("Other", ""))).is_err());
+    }
+
+    #[test]
+    fn test_meta_by_name_or_position() {
+        let task: Task = serde_json::from_str(
+            r#"{
+                "name": "A", "group": "", "url": "", "interactive": false,
+                "timeLimit": 1000, "testType": "single",
+                "input": {"type": "stdin"}, "output": {"type": "stdout"},
+                "tests": [
+                    {"input": "1", "output": "1", "source": "sample"},
+                    {"name": "big", "source": "stress", "enabled": false},
+                    {"tags": ["naive"], "expectedVerdict": "TL"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(test_meta(&task, "1").unwrap().has_tag("sample"));
+        assert!(!test_meta(&task, "big").unwrap().enabled);
+        assert!(test_meta(&task, "2").is_none());
+        let third = test_meta(&task, "3").unwrap();
+        assert!(third.enabled && third.has_tag("naive"));
+        assert_eq!(
+            Verdict::parse(third.expected_verdict.as_deref().unwrap()),
+            Some(Verdict::TimeLimit)
+        );
+    }
+}
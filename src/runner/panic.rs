@@ -0,0 +1,56 @@
+/// Extracts the panic message and location from a Rust program's stderr,
+/// e.g. `attempt to add with overflow at src/main.rs:12:5`.
+pub(crate) fn parse_panic(stderr: &str) -> Option<String> {
+    let mut lines = stderr.lines();
+    while let Some(line) = lines.next() {
+        let Some(rest) = line
+            .find("panicked at ")
+            .map(|pos| &line[pos + "panicked at ".len()..])
+        else {
+            continue;
+        };
+        // Rust 1.73+: "panicked at src/main.rs:12:5:" with the message below.
+        if let Some(location) = rest.strip_suffix(':') {
+            let message = lines.next().unwrap_or_default().trim();
+            return Some(format!("{} at {}", message, location));
+        }
+        // Older: "panicked at 'message', src/main.rs:12:5".
+        if let Some((message, location)) = rest.rsplit_once("', ") {
+            let message = message.strip_prefix('\'').unwrap_or(message);
+            return Some(format!("{} at {}", message, location));
+        }
+        return Some(rest.to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_panic;
+
+    #[test]
+    fn test_parse_panic() {
+        let stderr = "debug line\n\
+                      thread 'main' panicked at src/main.rs:12:5:\n\
+                      attempt to add with overflow\n\
+                      note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+        assert_eq!(
+            parse_panic(stderr).unwrap(),
+            "attempt to add with overflow at src/main.rs:12:5"
+        );
+    }
+
+    #[test]
+    fn test_parse_panic_old_format() {
+        let stderr = "thread 'main' panicked at 'index out of bounds: the len is 3 but the index is 3', src/main.rs:7:13\n";
+        assert_eq!(
+            parse_panic(stderr).unwrap(),
+            "index out of bounds: the len is 3 but the index is 3 at src/main.rs:7:13"
+        );
+    }
+
+    #[test]
+    fn test_parse_no_panic() {
+        assert_eq!(parse_panic("just some debug output\n"), None);
+    }
+}
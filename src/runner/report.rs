@@ -0,0 +1,297 @@
+use crate::runner::groups::{self, GroupResult};
+use crate::runner::{task_dir, TestResult, Verdict};
+use serde::Serialize;
+
+/// Results of running the tests once, on a single build of the task.
+pub(crate) struct Suite<'a> {
+    pub name: &'static str,
+    pub results: &'a [TestResult],
+}
+
+#[derive(Serialize)]
+struct JsonTest<'a> {
+    name: &'a str,
+    verdict: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_verdict: Option<&'static str>,
+    time_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    judge_time_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory_kb: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct JsonSuite<'a> {
+    name: &'static str,
+    passed: usize,
+    total: usize,
+    tests: Vec<JsonTest<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonGroup<'a> {
+    name: &'a str,
+    points: u64,
+    passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct JsonScore {
+    scored: u64,
+    total: u64,
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    task: &'a str,
+    url: &'a str,
+    suites: Vec<JsonSuite<'a>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<JsonGroup<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<JsonScore>,
+}
+
+fn passed(results: &[TestResult]) -> usize {
+    results
+        .iter()
+        .filter(|result| result.passed() && result.verdict != Verdict::Unknown)
+        .count()
+}
+
+fn failed(results: &[TestResult]) -> usize {
+    results.iter().filter(|result| !result.passed()).count()
+}
+
+fn to_json(task_name: &str, url: &str, suites: &[Suite], groups: &[GroupResult]) -> String {
+    let report = JsonReport {
+        task: task_name,
+        url,
+        suites: suites
+            .iter()
+            .map(|suite| JsonSuite {
+                name: suite.name,
+                passed: passed(suite.results),
+                total: suite.results.len(),
+                tests: suite
+                    .results
+                    .iter()
+                    .map(|result| JsonTest {
+                        name: &result.name,
+                        verdict: result.verdict.short(),
+                        expected_verdict: result.expected.map(|verdict| verdict.short()),
+                        time_ms: result.time.as_millis(),
+                        judge_time_ms: result.judge_time.map(|time| time.as_millis()),
+                        memory_kb: result.memory,
+                        message: result.message.as_deref(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        groups: groups
+            .iter()
+            .map(|group| JsonGroup {
+                name: &group.name,
+                points: group.points,
+                passed: group.passed(),
+                failure: group.failure.as_deref(),
+            })
+            .collect(),
+        score: (!groups.is_empty()).then(|| {
+            let (scored, total) = groups::score(groups);
+            JsonScore { scored, total }
+        }),
+    };
+    serde_json::to_string_pretty(&report).unwrap()
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&apos;"),
+            c if c.is_control() && c != '\n' && c != '\t' => {}
+            c => res.push(c),
+        }
+    }
+    res
+}
+
+fn seconds(results: &[TestResult]) -> f64 {
+    results.iter().map(|result| result.time.as_secs_f64()).sum()
+}
+
+fn to_junit(task_name: &str, suites: &[Suite]) -> String {
+    let total: usize = suites.iter().map(|suite| suite.results.len()).sum();
+    let failures: usize = suites.iter().map(|suite| failed(suite.results)).sum();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml += &format!(
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+        escape_xml(task_name),
+        total,
+        failures
+    );
+    for suite in suites {
+        xml += &format!(
+            "  <testsuite name=\"{}.{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            escape_xml(task_name),
+            suite.name,
+            suite.results.len(),
+            failed(suite.results),
+            seconds(suite.results)
+        );
+        for result in suite.results {
+            xml += &format!(
+                "    <testcase classname=\"{}.{}\" name=\"{}\" time=\"{:.3}\">\n",
+                escape_xml(task_name),
+                suite.name,
+                escape_xml(&result.name),
+                result.time.as_secs_f64()
+            );
+            if let Some(memory) = result.memory {
+                xml += &format!(
+                    "      <properties><property name=\"memory_kb\" value=\"{}\"/></properties>\n",
+                    memory
+                );
+            }
+            if result.verdict == Verdict::Unknown {
+                xml += "      <skipped message=\"no expected output\"/>\n";
+            } else if !result.passed() {
+                let message = result.message.as_deref().unwrap_or_default();
+                xml += &format!(
+                    "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                    result.verdict.short(),
+                    escape_xml(message.lines().next().unwrap_or_default()),
+                    escape_xml(message)
+                );
+            }
+            if !result.stderr.is_empty() {
+                xml += &format!(
+                    "      <system-err>{}</system-err>\n",
+                    escape_xml(&result.stderr)
+                );
+            }
+            xml += "    </testcase>\n";
+        }
+        xml += "  </testsuite>\n";
+    }
+    xml += "</testsuites>\n";
+    xml
+}
+
+/// Writes `report.json` and `report.xml` (JUnit) into the task directory.
+pub(crate) fn write(
+    task_name: &str,
+    url: &str,
+    suites: &[Suite],
+    groups: &[GroupResult],
+) -> Result<(), String> {
+    let dir = task_dir(task_name);
+    for (file, content) in [
+        ("report.json", to_json(task_name, url, suites, groups)),
+        ("report.xml", to_junit(task_name, suites)),
+    ] {
+        let path = format!("{}/{}", dir, file);
+        std::fs::write(&path, content).map_err(|err| format!("Can't write {}: {}", path, err))?;
+        println!("Report written to {}", path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_xml, to_json, to_junit, Suite};
+    use crate::runner::{TestResult, Verdict};
+    use std::time::Duration;
+
+    fn results() -> Vec<TestResult> {
+        vec![
+            TestResult {
+                name: "1".to_string(),
+                verdict: Verdict::Ok,
+                time: Duration::from_millis(12),
+                judge_time: Some(Duration::from_millis(18)),
+                memory: Some(2048),
+                message: None,
+                outputs: None,
+                stderr: String::new(),
+                expected: None,
+            },
+            TestResult {
+                name: "2".to_string(),
+                verdict: Verdict::WrongAnswer,
+                time: Duration::from_millis(3),
+                judge_time: None,
+                memory: None,
+                message: Some("line 1, token 1: expected '<3', found '4'".to_string()),
+                outputs: None,
+                stderr: "dbg".to_string(),
+                expected: None,
+            },
+            TestResult {
+                name: "3".to_string(),
+                verdict: Verdict::Unknown,
+                time: Duration::from_millis(1),
+                judge_time: None,
+                memory: None,
+                message: Some("no expected output".to_string()),
+                outputs: None,
+                stderr: String::new(),
+                expected: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("a<b & \"c\"\u{1}"), "a&lt;b &amp; &quot;c&quot;");
+    }
+
+    #[test]
+    fn test_json_report() {
+        let results = results();
+        let json = to_json(
+            "a_plus_b",
+            "https://codeforces.com/contest/1/problem/A",
+            &[Suite {
+                name: "release",
+                results: &results,
+            }],
+            &[],
+        );
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["task"], "a_plus_b");
+        assert_eq!(value["suites"][0]["passed"], 1);
+        assert_eq!(value["suites"][0]["tests"][0]["memory_kb"], 2048);
+        assert_eq!(value["suites"][0]["tests"][0]["judge_time_ms"], 18);
+        assert_eq!(value["suites"][0]["tests"][1]["verdict"], "WA");
+        assert!(value["suites"][0]["tests"][1].get("memory_kb").is_none());
+        assert!(value.get("groups").is_none());
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let results = results();
+        let xml = to_junit(
+            "a_plus_b",
+            &[Suite {
+                name: "release",
+                results: &results,
+            }],
+        );
+        assert!(xml.contains("<testsuites name=\"a_plus_b\" tests=\"3\" failures=\"1\">"));
+        assert!(xml.contains("<testcase classname=\"a_plus_b.release\" name=\"1\" time=\"0.012\">"));
+        assert!(xml.contains("<failure type=\"WA\" message=\"line 1, token 1: expected &apos;&lt;3&apos;, found &apos;4&apos;\">"));
+        assert!(xml.contains("<system-err>dbg</system-err>"));
+        assert!(xml.contains("<skipped message=\"no expected output\"/>"));
+    }
+}
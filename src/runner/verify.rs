@@ -0,0 +1,134 @@
+use crate::config::{Config, SiteConfig};
+use crate::runner::{all_passed, limits, run_suite, task_dir, task_tests, Parallelism, Program};
+use crate::submit::site_of;
+use crate::task_creator::task_name;
+use rust_competitive_helper_util::load_task;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const BUNDLE: &str = "main/src/main.rs";
+
+/// Scratch directory for standalone compilation and runs, removed when
+/// dropped.
+pub(crate) struct ScratchDir(pub PathBuf);
+
+impl ScratchDir {
+    pub(crate) fn new(purpose: &str) -> Result<Self, String> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "rust-competitive-helper-{}-{}-{}",
+            purpose,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&path)
+            .map_err(|err| format!("Can't create {}: {}", path.display(), err))?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+pub(crate) fn rustc_args(site: &SiteConfig) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(toolchain) = &site.toolchain {
+        args.push(format!("+{}", toolchain));
+    }
+    args.push("--edition".to_string());
+    args.push(site.edition.clone().unwrap_or_else(|| "2021".to_string()));
+    args.push("-O".to_string());
+    args.extend(site.rustc_flags.iter().cloned());
+    args
+}
+
+/// Compiles the bundle on its own with `rustc`, outside of the workspace, so
+/// nothing but the single file is available, just like on the judge. The
+/// toolchain, edition and flags come from the site's section in config.
+fn compile_bundle(dir: &Path, site: &SiteConfig) -> Result<PathBuf, String> {
+    let source = dir.join("main.rs");
+    std::fs::copy(BUNDLE, &source).map_err(|err| format!("Can't read {}: {}", BUNDLE, err))?;
+    let binary = dir.join(format!("solution{}", std::env::consts::EXE_SUFFIX));
+    let args = rustc_args(site);
+    println!("rustc {}", args.join(" "));
+    let status = Command::new("rustc")
+        .args(&args)
+        .arg("-o")
+        .arg(&binary)
+        .arg(&source)
+        .status()
+        .map_err(|err| format!("Couldn't run rustc: {}", err))?;
+    if !status.success() {
+        return Err(format!("{} doesn't compile on its own", BUNDLE));
+    }
+    Ok(binary)
+}
+
+/// Compiles `main/src/main.rs` standalone and runs the tests of the task it
+/// was built from against it. Returns `Ok(false)` when the bundle doesn't
+/// compile or a test fails.
+pub fn verify() -> Result<bool, String> {
+    let config = Config::load();
+    let task = load_task("main").ok_or_else(|| "main/task.json not found".to_string())?;
+    let task_name = task_name(&task);
+    let site = config.site(&site_of(&config, &task.url));
+    let dir = ScratchDir::new("verify")?;
+    let binary = match compile_bundle(&dir.0, &site) {
+        Ok(binary) => binary,
+        Err(err) => {
+            eprintln!("{}", err);
+            return Ok(false);
+        }
+    };
+    let tests = task_tests(&config, &task_name, &task, None);
+    if tests.is_empty() {
+        println!(
+            "Bundle compiles, no tests found in {}/tests",
+            task_dir(&task_name)
+        );
+        return Ok(true);
+    }
+    let parallelism = Parallelism::from_config(&config, None, false);
+    let program = Program::new(binary, &task)?;
+    let results = run_suite(
+        &program,
+        &tests,
+        &limits(&config, &task),
+        parallelism,
+        config.diff_style,
+    );
+    Ok(all_passed(&results))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rustc_args;
+    use crate::config::SiteConfig;
+
+    #[test]
+    fn test_rustc_args_default() {
+        assert_eq!(
+            rustc_args(&SiteConfig::default()),
+            vec!["--edition", "2021", "-O"]
+        );
+    }
+
+    #[test]
+    fn test_rustc_args_site() {
+        let site = SiteConfig {
+            toolchain: Some("1.70.0".to_string()),
+            edition: Some("2018".to_string()),
+            rustc_flags: vec!["-C".to_string(), "opt-level=2".to_string()],
+            time_multiplier: None,
+            source_limit: None,
+        };
+        assert_eq!(
+            rustc_args(&site),
+            vec!["+1.70.0", "--edition", "2018", "-O", "-C", "opt-level=2"]
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use crate::config::Config;
+use crate::runner::{
+    build, groups, limits, load_task_or_fail, print_details, run_all, task_dir, task_tests,
+    Parallelism, Profile, Program, TestResult,
+};
+use crossterm::execute;
+use crossterm::style::{Color, ResetColor, SetForegroundColor};
+use crossterm::terminal::{Clear, ClearType};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const RERUN_PREFIX: &str = "cargo:rerun-if-changed=";
+
+/// Library directories the task's build script asked cargo to watch, read
+/// back from the newest build script output of the task.
+fn library_dirs(task_name: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir("target/release/build") else {
+        return Vec::new();
+    };
+    let prefix = format!("{}-", task_name);
+    let output = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .map(|entry| entry.path().join("output"))
+        .filter_map(|path| Some((path.metadata().ok()?.modified().ok()?, path)))
+        .max()
+        .and_then(|(_, path)| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    parse_rerun_paths(&output)
+        .into_iter()
+        .map(|path| Path::new(&task_dir(task_name)).join(path))
+        .collect()
+}
+
+fn parse_rerun_paths(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix(RERUN_PREFIX))
+        .filter(|path| *path != ".")
+        .map(str::to_string)
+        .collect()
+}
+
+fn collect_modified(path: &Path, result: &mut BTreeMap<PathBuf, SystemTime>) {
+    let Ok(metadata) = path.metadata() else {
+        return;
+    };
+    if metadata.is_dir() {
+        if path.file_name().is_some_and(|name| name == "target") {
+            return;
+        }
+        if let Ok(entries) = std::fs::read_dir(path) {
+            for entry in entries.flatten() {
+                collect_modified(&entry.path(), result);
+            }
+        }
+    } else if let Ok(modified) = metadata.modified() {
+        result.insert(path.to_path_buf(), modified);
+    }
+}
+
+fn snapshot(paths: &[PathBuf]) -> BTreeMap<PathBuf, SystemTime> {
+    let mut result = BTreeMap::new();
+    for path in paths {
+        collect_modified(path, &mut result);
+    }
+    result
+}
+
+fn print_table(results: &[TestResult]) {
+    let mut stdout = std::io::stdout();
+    let width = results
+        .iter()
+        .map(|result| result.name.len().max(3))
+        .max()
+        .unwrap_or(3);
+    print!("{:<6}", "test");
+    for result in results {
+        print!(" {:>width$}", result.name);
+    }
+    println!();
+    print!("{:<6}", "");
+    for result in results {
+        let _ = execute!(stdout, SetForegroundColor(result.color()));
+        print!(" {:>width$}", result.verdict.short());
+        let _ = execute!(stdout, ResetColor);
+    }
+    println!();
+    print!("{:<6}", "ms");
+    for result in results {
+        print!(" {:>width$}", result.time.as_millis());
+    }
+    println!();
+    if results.iter().any(|result| result.judge_time.is_some()) {
+        print!("{:<6}", "judge");
+        for result in results {
+            let time = result.judge_time.map(|time| time.as_millis().to_string());
+            print!(" {:>width$}", time.unwrap_or_default());
+        }
+        println!();
+    }
+    let passed = results.iter().filter(|result| result.passed()).count();
+    let color = if passed == results.len() {
+        Color::Green
+    } else {
+        Color::Red
+    };
+    let _ = execute!(stdout, SetForegroundColor(color));
+    println!("Passed {}/{}", passed, results.len());
+    let _ = execute!(stdout, ResetColor);
+}
+
+fn rerun(config: &Config, task_name: &str) -> Result<Vec<PathBuf>, String> {
+    let mut stdout = std::io::stdout();
+    let _ = execute!(
+        stdout,
+        Clear(ClearType::All),
+        crossterm::cursor::MoveTo(0, 0)
+    );
+    let task = load_task_or_fail(task_name)?;
+    let program = Program::new(build(task_name, Profile::Release)?, &task)?;
+    let tests = task_tests(config, task_name, &task, None);
+    let limits = limits(config, &task);
+    let parallelism = Parallelism::from_config(config, None, false);
+    let results = run_all(&program, &tests, &limits, parallelism, |_| {});
+    let _ = execute!(
+        stdout,
+        Clear(ClearType::All),
+        crossterm::cursor::MoveTo(0, 0)
+    );
+    println!(
+        "{} @ {}",
+        task_name,
+        chrono::Local::now().format("%H:%M:%S")
+    );
+    print_table(&results);
+    if !task.groups.is_empty() {
+        groups::print(&groups::evaluate(&task.groups, &results));
+    }
+    for result in &results {
+        if result.message.is_some() || result.outputs.is_some() {
+            println!("{}:", result.name);
+            print_details(result, config.diff_style);
+        }
+    }
+    Ok(library_dirs(task_name))
+}
+
+/// Rebuilds the task and re-runs its tests whenever its sources, tests or
+/// the libraries it uses change. Runs until interrupted.
+pub fn watch(task_name: &str) -> Result<(), String> {
+    let config = Config::load();
+    load_task_or_fail(task_name)?;
+    let dir = task_dir(task_name);
+    let own = [
+        Path::new(&dir).join("src"),
+        Path::new(&dir).join("tests"),
+        Path::new(&dir).join("task.json"),
+    ];
+    let watched = |libraries: &[PathBuf]| {
+        let paths = own.iter().chain(libraries).cloned().collect::<Vec<_>>();
+        snapshot(&paths)
+    };
+    let mut libraries = Vec::new();
+    let mut last = None;
+    loop {
+        let current = watched(&libraries);
+        if last.as_ref() != Some(&current) {
+            // Taken before the run, so files saved while it builds or runs
+            // trigger the next one.
+            let mut baseline = current;
+            match rerun(&config, task_name) {
+                Ok(dirs) => {
+                    let added = dirs
+                        .iter()
+                        .filter(|dir| !libraries.contains(dir))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    baseline.extend(snapshot(&added));
+                    baseline.retain(|path, _| {
+                        own.iter().chain(&dirs).any(|root| path.starts_with(root))
+                    });
+                    libraries = dirs;
+                }
+                Err(err) => eprintln!("{}", err),
+            }
+            println!("Watching for changes, press Ctrl+C to stop");
+            last = Some(baseline);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_rerun_paths;
+
+    #[test]
+    fn test_parse_rerun_paths() {
+        let output = "cargo:rerun-if-changed=.\n\
+                      cargo:rerun-if-changed=../../algo_lib\n\
+                      cargo:warning=unrelated\n\
+                      cargo:rerun-if-changed=../../marathon_utils\n";
+        assert_eq!(
+            parse_rerun_paths(output),
+            vec!["../../algo_lib", "../../marathon_utils"]
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::runner::{self, Profile};
+use crate::submit::history;
+use crate::submit::{failure, BUNDLE};
+use rust_competitive_helper_util::load_task;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Rebuilds the bundle for `task_name` and checks that `main/` now holds
+/// that task. Returns the task directory.
+pub(crate) fn prepare(task_name: &str) -> Result<String, String> {
+    let task = runner::load_task_or_fail(task_name)?;
+    runner::build(task_name, Profile::Release)?;
+    let main = load_task("main").ok_or_else(|| "main/task.json not found".to_string())?;
+    if !runner::same_task(&main, &task) {
+        return Err(format!(
+            "main/task.json is {} ({}) but tasks/{}/task.json is {} ({}), not submitting",
+            main.name, main.url, task_name, task.name, task.url
+        ));
+    }
+    Ok(runner::task_dir(task_name))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata().ok()?.modified().ok()
+}
+
+/// The most recently modified file under `path`.
+fn newest(path: &Path) -> Option<(SystemTime, PathBuf)> {
+    if !path.is_dir() {
+        return modified(path).map(|time| (time, path.to_path_buf()));
+    }
+    std::fs::read_dir(path)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| newest(&entry.path()))
+        .max()
+}
+
+/// The task source changed after `bundle` was generated, if any.
+fn stale_source(task_dir: &str, bundle: &Path) -> Option<PathBuf> {
+    let bundled = modified(bundle)?;
+    [
+        Path::new(task_dir).join("src"),
+        Path::new(task_dir).join("task.json"),
+    ]
+    .iter()
+    .filter_map(|path| newest(path))
+    .max()
+    .filter(|(time, _)| *time > bundled)
+    .map(|(_, path)| path)
+}
+
+/// Warns when `main/` doesn't belong to any task or its task's sources
+/// changed after the bundle was generated. Returns the directory of the
+/// task `main/` was built from.
+pub(crate) fn warn_if_stale() -> Option<String> {
+    let Some(task) = load_task("main") else {
+        failure("WARNING: main/task.json not found, can't tell which task the bundle is for");
+        return None;
+    };
+    let Some(dir) = history::task_dir_of(&task) else {
+        failure(&format!(
+            "WARNING: no task in tasks/ matches main/task.json ({}), the bundle may be stale",
+            task.url
+        ));
+        return None;
+    };
+    if let Some(path) = stale_source(&dir, Path::new(BUNDLE)) {
+        let name = dir.trim_start_matches("tasks/");
+        failure(&format!(
+            "WARNING: {} changed after {} was generated, the bundle is stale. \
+             Use `submit {}` to rebuild it first.",
+            path.display(),
+            BUNDLE,
+            name
+        ));
+    }
+    Some(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::stale_source;
+    use crate::runner::ScratchDir;
+    use std::fs::File;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_stale_source() {
+        let dir = ScratchDir::new("stale").unwrap();
+        let task = dir.0.join("task");
+        std::fs::create_dir_all(task.join("src")).unwrap();
+        let bundle = dir.0.join("main.rs");
+        let now = SystemTime::now();
+        for (path, age) in [
+            (task.join("task.json"), 30),
+            (task.join("src/main.rs"), 20),
+            (bundle.clone(), 10),
+        ] {
+            File::create(&path)
+                .unwrap()
+                .set_modified(now - Duration::from_secs(age))
+                .unwrap();
+        }
+        let task = task.to_str().unwrap();
+        assert_eq!(stale_source(task, &bundle), None);
+        let source = dir.0.join("task/src/solution.rs");
+        File::create(&source).unwrap().set_modified(now).unwrap();
+        assert_eq!(stale_source(task, &bundle), Some(source));
+    }
+}
@@ -0,0 +1,108 @@
+use clipboard::{ClipboardContext, ClipboardProvider};
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Where the bundle ended up when it couldn't be submitted.
+pub(crate) enum Copied {
+    Clipboard,
+    /// Sent to the terminal as an OSC 52 escape sequence, which the
+    /// terminal may or may not support.
+    Terminal,
+    File(PathBuf),
+}
+
+/// Written when no clipboard is available.
+const FALLBACK_FILE: &str = "target/submission.rs";
+
+fn wayland(text: &str) -> bool {
+    if std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        return false;
+    }
+    let Ok(mut child) = Command::new("wl-copy")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+    let written = child
+        .stdin
+        .take()
+        .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+    child.wait().is_ok_and(|status| status.success()) && written
+}
+
+fn x11(text: &str) -> bool {
+    if cfg!(target_os = "linux") && std::env::var_os("DISPLAY").is_none() {
+        return false;
+    }
+    ClipboardProvider::new()
+        .and_then(|mut ctx: ClipboardContext| ctx.set_contents(text.to_string()))
+        .is_ok()
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut res = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let triple = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                res.push(ALPHABET[(triple >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                res.push('=');
+            }
+        }
+    }
+    res
+}
+
+fn osc52(text: &str) -> bool {
+    let mut stdout = std::io::stdout();
+    if !stdout.is_terminal() {
+        return false;
+    }
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))
+        .and_then(|()| stdout.flush())
+        .is_ok()
+}
+
+/// Copies `text` to the Wayland or X11 clipboard, then through the
+/// terminal, then writes it to a file when neither is available.
+pub(crate) fn copy(text: &str) -> Result<Copied, String> {
+    if wayland(text) || x11(text) {
+        return Ok(Copied::Clipboard);
+    }
+    if osc52(text) {
+        return Ok(Copied::Terminal);
+    }
+    let path = PathBuf::from(FALLBACK_FILE);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|err| format!("Can't create {}: {}", dir.display(), err))?;
+    }
+    std::fs::write(&path, text)
+        .map_err(|err| format!("Can't write {}: {}", path.display(), err))?;
+    Ok(Copied::File(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::base64;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"fn main() {}\n"), "Zm4gbWFpbigpIHt9Cg==");
+    }
+}
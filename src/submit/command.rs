@@ -0,0 +1,265 @@
+use regex::Regex;
+use std::fmt;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+
+/// Why a submit command failed.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum SubmitError {
+    /// The tool isn't installed or can't be started.
+    ToolMissing(String),
+    /// The judge didn't accept the credentials.
+    Auth(String),
+    /// The judge refused the submission, e.g. an unknown language.
+    Rejected(String),
+    /// The judge couldn't be reached.
+    Network(String),
+    /// The tool failed for another reason.
+    Failed(String),
+}
+
+impl SubmitError {
+    /// Whether trying again later may succeed, e.g. once the judge is back
+    /// up or stops rate limiting.
+    pub(crate) fn is_transient(&self) -> bool {
+        matches!(self, SubmitError::Network(_))
+    }
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::ToolMissing(message) => write!(f, "tool missing: {}", message),
+            SubmitError::Auth(message) => write!(f, "authentication failed: {}", message),
+            SubmitError::Rejected(message) => write!(f, "rejected by the judge: {}", message),
+            SubmitError::Network(message) => write!(f, "network error: {}", message),
+            SubmitError::Failed(message) => write!(f, "failed: {}", message),
+        }
+    }
+}
+
+// Matched as whole words in lowercase stderr, so that e.g. a contest
+// number in a URL isn't taken for an HTTP status. Some words take a suffix
+// to cover exception names such as `ConnectionError` and `SSLError`.
+const AUTH: &str =
+    r"\b(login|log in|logged in|password|unauthori[sz]ed|authenticat\w*|credentials?|forbidden)\b";
+const NETWORK: &str = concat!(
+    r"\b(connection(\w*error)?|timed out|\w*timeout(error)?|network|resolve|unreachable|dns|",
+    r"ssl(error)?|too many requests|rate limit(ed)?|429|502|503|temporarily unavailable)\b"
+);
+const REJECTED: &str =
+    r"\b(language|rejected|not allowed|unsupported|too long|already submitted)\b";
+
+/// Prefixes of the lines tools report their failure on, in lowercase.
+const ERROR_TAGS: [&str; 4] = ["[error]", "[failure]", "error:", "fatal:"];
+
+/// The lines of `stderr` that say why the command failed: those tagged as
+/// errors, or else the last non-empty line. Progress lines such as
+/// "Logged in as ..." before the failure are left out.
+fn failing_lines(stderr: &str) -> Vec<&str> {
+    let mut lines = stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let tagged = lines
+        .clone()
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            ERROR_TAGS.iter().any(|tag| lower.starts_with(tag))
+        })
+        .collect::<Vec<_>>();
+    if tagged.is_empty() {
+        lines.next_back().into_iter().collect()
+    } else {
+        tagged
+    }
+}
+
+/// Classifies a failed run of `program` by the lines of stderr that report
+/// the failure. Authentication is checked first, as tools mention the URL
+/// they failed to log in to.
+pub(crate) fn classify(program: &str, status: &str, stderr: &str) -> SubmitError {
+    let lines = failing_lines(stderr);
+    let lower = lines.join("\n").to_lowercase();
+    let message = lines.last().map_or_else(
+        || format!("{} exited with {}", program, status),
+        |line| line.to_string(),
+    );
+    let mentions = |words: &str| Regex::new(words).unwrap().is_match(&lower);
+    if mentions(AUTH) {
+        SubmitError::Auth(message)
+    } else if mentions(NETWORK) {
+        SubmitError::Network(message)
+    } else if mentions(REJECTED) {
+        SubmitError::Rejected(message)
+    } else {
+        SubmitError::Failed(message)
+    }
+}
+
+/// Copies `from` to `to` as it comes, so prompts show up, and returns
+/// everything that was read.
+fn tee(mut from: impl Read, mut to: impl Write) -> String {
+    let mut seen = Vec::new();
+    let mut buffer = [0; 4096];
+    while let Ok(n @ 1..) = from.read(&mut buffer) {
+        let _ = to.write_all(&buffer[..n]);
+        let _ = to.flush();
+        seen.extend_from_slice(&buffer[..n]);
+    }
+    String::from_utf8_lossy(&seen).into_owned()
+}
+
+/// Where the command's output is shown, nowhere when `quiet`.
+fn echo(quiet: bool, stream: impl Write + Send + 'static) -> Box<dyn Write + Send> {
+    if quiet {
+        Box::new(std::io::sink())
+    } else {
+        Box::new(stream)
+    }
+}
+
+/// Runs a submit command. Its output is shown as it comes unless `quiet`
+/// and kept, so the submission id and verdict can be taken from it and
+/// stderr can tell why the command failed. Returns stdout and stderr of a
+/// successful run.
+pub(crate) fn run(
+    args: &[String],
+    install_hint: Option<&str>,
+    quiet: bool,
+) -> Result<String, SubmitError> {
+    let Some((program, args)) = args.split_first() else {
+        return Err(SubmitError::Failed("empty command".to_string()));
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if quiet {
+            Stdio::null()
+        } else {
+            Stdio::inherit()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| {
+            SubmitError::ToolMissing(format!(
+                "can't run {}: {}. {}",
+                program,
+                err,
+                install_hint.unwrap_or_default()
+            ))
+        })?;
+    let stderr = child.stderr.take().unwrap();
+    let stderr = std::thread::spawn(move || tee(stderr, echo(quiet, std::io::stderr())));
+    let stdout = tee(child.stdout.take().unwrap(), echo(quiet, std::io::stdout()));
+    let stderr = stderr.join().unwrap_or_default();
+    let status = child
+        .wait()
+        .map_err(|err| SubmitError::Failed(err.to_string()))?;
+    if status.success() {
+        Ok(stdout + &stderr)
+    } else {
+        Err(classify(program, &status.to_string(), &stderr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, SubmitError};
+
+    #[test]
+    fn test_classify() {
+        assert!(matches!(
+            classify("oj", "exit status: 1", "[ERROR] You are not logged in\n"),
+            SubmitError::Auth(_)
+        ));
+        assert!(matches!(
+            classify(
+                "submitter",
+                "exit status: 1",
+                "error: Connection refused (os error 111)"
+            ),
+            SubmitError::Network(_)
+        ));
+        assert!(matches!(
+            classify(
+                "dmoj-submit",
+                "exit status: 2",
+                "Language rust is not available\n"
+            ),
+            SubmitError::Rejected(_)
+        ));
+        assert!(matches!(
+            classify(
+                "oj",
+                "exit status: 1",
+                "Login failed for https://codeforces.com/contest/1502/problem/A\n"
+            ),
+            SubmitError::Auth(_)
+        ));
+        assert!(matches!(
+            classify(
+                "oj",
+                "exit status: 1",
+                "requests.exceptions.ConnectionError: HTTPSConnectionPool\n"
+            ),
+            SubmitError::Network(_)
+        ));
+        assert!(matches!(
+            classify(
+                "submitter",
+                "exit status: 1",
+                "HTTP 503 Service Unavailable"
+            ),
+            SubmitError::Network(_)
+        ));
+        assert!(matches!(
+            classify(
+                "submitter",
+                "exit status: 1",
+                "Failed to parse https://codeforces.com/contest/1429/problem/A"
+            ),
+            SubmitError::Failed(_)
+        ));
+        assert_eq!(
+            classify("submitter", "exit status: 3", ""),
+            SubmitError::Failed("submitter exited with exit status: 3".to_string())
+        );
+        assert_eq!(
+            classify(
+                "submitter",
+                "exit status: 3",
+                "warning\nsomething broke\n\n"
+            ),
+            SubmitError::Failed("something broke".to_string())
+        );
+    }
+
+    #[test]
+    fn test_classify_failing_lines() {
+        assert_eq!(
+            classify(
+                "oj",
+                "exit status: 1",
+                "Logged in as tourist\nConnection reset by peer\n"
+            ),
+            SubmitError::Network("Connection reset by peer".to_string())
+        );
+        assert_eq!(
+            classify(
+                "oj",
+                "exit status: 1",
+                "[INFO] login: tourist\n[ERROR] connection timed out\n[HINT] try again later\n"
+            ),
+            SubmitError::Network("[ERROR] connection timed out".to_string())
+        );
+        assert!(matches!(
+            classify(
+                "oj",
+                "exit status: 1",
+                "[INFO] network is up\n[FAILURE] You are not logged in\n"
+            ),
+            SubmitError::Auth(_)
+        ));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::archiver;
+use crate::config::Config;
+use crate::runner;
+use crate::submit::history::Submission;
+use crate::submit::{
+    bundle, failure, fits, queue, read_main_url, record_submission, submit_with_fallbacks, Request,
+    Sent, BUNDLE,
+};
+use rust_competitive_helper_util::read_from_file;
+use std::time::Duration;
+
+enum Outcome {
+    Submitted(Submission),
+    Skipped(String),
+    Failed(String),
+}
+
+impl Outcome {
+    fn describe(&self) -> String {
+        match self {
+            Outcome::Submitted(submission) => {
+                let mut res = format!("submitted with {}", submission.judge);
+                let details = [submission.id.as_deref(), submission.verdict.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>();
+                if !details.is_empty() {
+                    res += &format!(" ({})", details.join(", "));
+                }
+                res
+            }
+            Outcome::Skipped(reason) => format!("skipped: {}", reason),
+            Outcome::Failed(reason) => format!("failed: {}", reason),
+        }
+    }
+}
+
+/// Builds, verifies and submits one task of the contest. A task whose
+/// tests fail is skipped unless `force` is set.
+fn submit_task(config: &Config, task_name: &str, force: bool) -> Outcome {
+    let dir = match bundle::prepare(task_name) {
+        Ok(dir) => dir,
+        Err(err) => return Outcome::Failed(err),
+    };
+    let url = read_main_url().unwrap_or_default();
+    if !fits(config, &url, Some(&dir)) {
+        return Outcome::Skipped("over the source size limit".to_string());
+    }
+    match runner::verify() {
+        Ok(true) => {}
+        Ok(false) if !force => return Outcome::Skipped("tests fail".to_string()),
+        Ok(false) => failure("Tests fail, submitting anyway"),
+        Err(err) => return Outcome::Failed(err),
+    }
+    let bundle = read_from_file(BUNDLE).unwrap_or_default();
+    let request = Request {
+        url: &url,
+        file: BUNDLE,
+        language: None,
+        quiet: false,
+    };
+    match submit_with_fallbacks(config, &request) {
+        Ok(Sent::Submitted { judge, output }) => {
+            let submission = Submission::new(&judge, &bundle, &output);
+            record_submission(Some(dir), submission.clone());
+            Outcome::Submitted(submission)
+        }
+        Ok(Sent::Failed {
+            judge,
+            reason,
+            transient: true,
+        }) if queue::enqueue(&judge, &url, Some(dir), &bundle, &reason) => {
+            Outcome::Failed(format!("queued for retry: {}", reason))
+        }
+        Ok(Sent::Failed { reason, .. }) => Outcome::Failed(reason),
+        Ok(Sent::Unsupported) => Outcome::Failed("no judge submitted it".to_string()),
+        Err(err) => Outcome::Failed(err),
+    }
+}
+
+/// Submits every task of `contest` in name order, waiting `delay` between
+/// submissions. Tasks whose bundle fails its tests are skipped unless
+/// `force` is set.
+pub(crate) fn submit_contest(config: &Config, contest: &str, delay: Duration, force: bool) {
+    let contests = archiver::contest_list();
+    let Some((_, tasks)) = contests.iter().find(|(name, _)| name == contest) else {
+        failure(&format!(
+            "Contest not found: {}. Known contests: {}",
+            contest,
+            contests
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        return;
+    };
+    let mut tasks = tasks.clone();
+    tasks.sort();
+    let mut results = Vec::new();
+    for (i, task_name) in tasks.iter().enumerate() {
+        println!("[{}/{}] {}", i + 1, tasks.len(), task_name);
+        let outcome = submit_task(config, task_name, force);
+        println!("{}: {}", task_name, outcome.describe());
+        let submitted = matches!(outcome, Outcome::Submitted(_));
+        results.push((task_name, outcome));
+        if submitted && i + 1 < tasks.len() && !delay.is_zero() {
+            println!("Waiting {} s before the next submission", delay.as_secs());
+            std::thread::sleep(delay);
+        }
+    }
+    println!("Contest {}:", contest);
+    for (task_name, outcome) in &results {
+        let line = format!("  {}: {}", task_name, outcome.describe());
+        match outcome {
+            Outcome::Submitted(_) => println!("{}", line),
+            Outcome::Skipped(_) | Outcome::Failed(_) => failure(&line),
+        }
+    }
+    let submitted = results
+        .iter()
+        .filter(|(_, outcome)| matches!(outcome, Outcome::Submitted(_)))
+        .count();
+    println!("Submitted {}/{}", submitted, results.len());
+}
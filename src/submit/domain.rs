@@ -0,0 +1,104 @@
+use crate::config::Config;
+use publicsuffix::{IcannList, Psl};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// The Public Suffix List from https://publicsuffix.org/list/. Replace the
+/// file with a newer copy to pick up new suffixes.
+const PUBLIC_SUFFIX_LIST: &str = include_str!("public_suffix_list.dat");
+
+/// The ICANN section of the list, without suffixes private registries
+/// hand out such as `github.io`.
+fn public_suffixes() -> &'static IcannList {
+    static LIST: OnceLock<IcannList> = OnceLock::new();
+    LIST.get_or_init(|| {
+        PUBLIC_SUFFIX_LIST
+            .parse()
+            .expect("Can't parse the public suffix list")
+    })
+}
+
+/// The label `host` is registered under, right before its public suffix,
+/// e.g. `codeforces` for `m1.codeforces.com` and `codeforces.ml`, or
+/// `luogu` for `www.luogu.com.cn`. A host without a registrable domain,
+/// such as `localhost`, gives its first label.
+pub(crate) fn registered_name(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_lowercase();
+    let name = match public_suffixes().domain(host.as_bytes()) {
+        Some(domain) => String::from_utf8_lossy(domain.as_bytes()).into_owned(),
+        None => host,
+    };
+    name.split('.').next().unwrap_or_default().to_string()
+}
+
+/// The judge of the first entry of `config.aliases` whose pattern matches
+/// `host`.
+pub(crate) fn alias<'a>(config: &'a Config, host: &str) -> Result<Option<&'a str>, String> {
+    for alias in &config.aliases {
+        let pattern = Regex::new(&alias.host)
+            .map_err(|err| format!("Invalid alias pattern {}: {}", alias.host, err))?;
+        if pattern.is_match(host) {
+            return Ok(Some(&alias.judge));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{alias, registered_name};
+    use crate::config::{AliasConfig, Config};
+
+    #[test]
+    fn test_registered_name() {
+        for (host, name) in [
+            ("codeforces.com", "codeforces"),
+            ("m1.codeforces.com", "codeforces"),
+            ("mirror.codeforces.com", "codeforces"),
+            ("codeforces.ml", "codeforces"),
+            ("Codeforces.COM.", "codeforces"),
+            ("atcoder.jp", "atcoder"),
+            ("abc300.contest.atcoder.jp", "atcoder"),
+            ("acm.timus.ru", "timus"),
+            ("www.luogu.com.cn", "luogu"),
+            ("luogu.com.cn", "luogu"),
+            ("judge.u-aizu.ac.jp", "u-aizu"),
+            ("szkopul.edu.pl", "szkopul"),
+            ("sio2.mimuw.edu.pl", "mimuw"),
+            ("acm.hdu.edu.cn", "hdu"),
+            ("www.spoj.com", "spoj"),
+            ("onlinejudge.org", "onlinejudge"),
+            ("com.cn", "com"),
+            ("contest.ucup.ac", "ucup"),
+            ("example.xyz", "example"),
+            ("localhost", "localhost"),
+        ] {
+            assert_eq!(registered_name(host), name, "{}", host);
+        }
+    }
+
+    #[test]
+    fn test_alias() {
+        let mut config = Config::default();
+        config.aliases.push(AliasConfig {
+            host: r"(^|\.)codeforc\.es$".to_string(),
+            judge: "codeforces".to_string(),
+        });
+        assert_eq!(alias(&config, "codeforc.es").unwrap(), Some("codeforces"));
+        assert_eq!(alias(&config, "codeforces.com").unwrap(), None);
+        // The first matching entry wins, in the order they are written.
+        config.aliases.insert(
+            0,
+            AliasConfig {
+                host: r"^codeforc\.es$".to_string(),
+                judge: "cf-oj".to_string(),
+            },
+        );
+        assert_eq!(alias(&config, "codeforc.es").unwrap(), Some("cf-oj"));
+        config.aliases.push(AliasConfig {
+            host: "(".to_string(),
+            judge: "x".to_string(),
+        });
+        assert!(alias(&config, "codeforces.com").is_err());
+    }
+}
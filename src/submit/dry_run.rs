@@ -0,0 +1,184 @@
+use crate::config::{Config, JudgeConfig};
+use crate::submit::size::kb;
+use crate::submit::{
+    bundle, domain, history, judge, read_main_url, site_of, SubmitOptions, BUNDLE,
+};
+use rust_competitive_helper_util::load_task;
+
+fn command_line(args: &[String]) -> String {
+    args.join(" ")
+}
+
+/// Where the judge comes from, for telling a config entry from a built-in
+/// one with the same name.
+fn origin(config: &Config, judge: &JudgeConfig) -> &'static str {
+    if config.judges.iter().any(|other| other.name == judge.name) {
+        "config.toml"
+    } else {
+        "built-in"
+    }
+}
+
+/// How a submission to `url` would be made, as label and value pairs.
+fn plan(config: &Config, url: &str) -> Result<Vec<(&'static str, String)>, String> {
+    let mut lines = vec![
+        ("Site", site_of(config, url)),
+        ("Host", judge::host(url).unwrap_or("-").to_string()),
+    ];
+    if let Some(host) = judge::host(url) {
+        if let Some(alias) = domain::alias(config, host)? {
+            lines.push(("Alias", format!("{} (config.toml aliases)", alias)));
+        }
+    }
+    let Some(judge) = judge::find(config, url)? else {
+        lines.push(("Judge", "none matches, nothing would be run".to_string()));
+        lines.push(("Fallback", "clipboard".to_string()));
+        return Ok(lines);
+    };
+    lines.push((
+        "Judge",
+        format!(
+            "{} ({}, host pattern {})",
+            judge.name,
+            origin(config, &judge),
+            judge.host
+        ),
+    ));
+    if let Some(login) = &judge.login {
+        lines.push((
+            "Login",
+            command_line(&judge::expand(&judge, login, url, BUNDLE)?),
+        ));
+    }
+    lines.push((
+        "Command",
+        command_line(&judge::expand(&judge, &judge.command, url, BUNDLE)?),
+    ));
+    if let Some(limit) = judge.source_limit {
+        lines.push(("Size limit", kb(limit)));
+    }
+    let mut fallbacks = Vec::new();
+    for fallback in judge::chain(config, judge)?.into_iter().skip(1) {
+        fallbacks.push(format!(
+            "{}: {}",
+            fallback.name,
+            command_line(&judge::expand(&fallback, &fallback.command, url, BUNDLE)?)
+        ));
+    }
+    fallbacks.push("clipboard".to_string());
+    lines.push(("Fallback", fallbacks.join(", then ")));
+    Ok(lines)
+}
+
+/// Prints what `submit` would do without building, logging in or
+/// submitting anything.
+pub(crate) fn explain(config: &Config, options: &SubmitOptions) -> Result<(), String> {
+    match &options.task {
+        Some(task_name) => println!(
+            "Would rebuild the bundle of tasks/{} first, showing main/ as it is now",
+            task_name
+        ),
+        None => {
+            bundle::warn_if_stale();
+        }
+    }
+    let mut lines = Vec::new();
+    match load_task("main") {
+        Some(task) => {
+            lines.push(("Task", format!("{} (main/task.json)", task.name)));
+            if let Some(dir) = history::task_dir_of(&task) {
+                lines.push(("Task dir", dir));
+            }
+        }
+        None => lines.push(("Task", "main/task.json not found".to_string())),
+    }
+    let url = read_main_url().unwrap_or_default();
+    lines.push(("URL", url.clone()));
+    lines.push((
+        "Bundle",
+        match std::fs::read_to_string(BUNDLE) {
+            Ok(code) => format!(
+                "{}, {}, sha256 {}",
+                BUNDLE,
+                kb(code.len() as u64),
+                history::bundle_hash(&code)
+            ),
+            Err(err) => format!("{}: {}", BUNDLE, err),
+        },
+    ));
+    lines.extend(plan(config, &url)?);
+    let width = lines
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+    for (label, value) in lines {
+        println!(
+            "{:width$}  {}",
+            format!("{}:", label),
+            value,
+            width = width + 1
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plan;
+    use crate::config::{AliasConfig, Config};
+    use crate::submit::judge::test_judge;
+
+    fn value(lines: &[(&str, String)], label: &str) -> Option<String> {
+        lines
+            .iter()
+            .find(|(other, _)| *other == label)
+            .map(|(_, value)| value.clone())
+    }
+
+    #[test]
+    fn test_plan() {
+        let mut config = Config::default();
+        let lines = plan(&config, "https://codeforces.com/contest/1/problem/A").unwrap();
+        assert_eq!(value(&lines, "Site").as_deref(), Some("codeforces"));
+        assert_eq!(
+            value(&lines, "Command").as_deref(),
+            Some("submitter https://codeforces.com/contest/1/problem/A rust main/src/main.rs")
+        );
+        assert_eq!(value(&lines, "Fallback").as_deref(), Some("clipboard"));
+
+        config
+            .judges
+            .push(test_judge("mirror", r"^mirror\.example$", &["codeforces"]));
+        let lines = plan(&config, "https://mirror.example/problem/a b").unwrap();
+        assert_eq!(
+            value(&lines, "Judge").as_deref(),
+            Some(r"mirror (config.toml, host pattern ^mirror\.example$)")
+        );
+        assert_eq!(
+            value(&lines, "Command").as_deref(),
+            Some("oj submit \"https://mirror.example/problem/a b\"")
+        );
+        assert!(value(&lines, "Fallback")
+            .unwrap()
+            .starts_with("codeforces: submitter"));
+
+        config.aliases.push(AliasConfig {
+            host: r"^cf\.example$".to_string(),
+            judge: "codeforces".to_string(),
+        });
+        let lines = plan(&config, "https://cf.example/contest/1/problem/A").unwrap();
+        assert_eq!(value(&lines, "Site").as_deref(), Some("codeforces"));
+        assert_eq!(
+            value(&lines, "Alias").as_deref(),
+            Some("codeforces (config.toml aliases)")
+        );
+        assert!(value(&lines, "Judge")
+            .unwrap()
+            .starts_with("codeforces (built-in"));
+
+        let lines = plan(&config, "https://unknown.example/x").unwrap();
+        assert_eq!(value(&lines, "Host").as_deref(), Some("unknown.example"));
+        assert_eq!(value(&lines, "Fallback").as_deref(), Some("clipboard"));
+    }
+}
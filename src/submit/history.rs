@@ -0,0 +1,189 @@
+use crate::runner;
+use chrono::Local;
+use regex::Regex;
+use rust_competitive_helper_util::{load_task, Task};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+const FILE: &str = "submissions.json";
+
+/// One submission of a task, as stored in `tasks/<name>/submissions.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Submission {
+    pub timestamp: String,
+    pub judge: String,
+    /// SHA-256 of the submitted bundle.
+    pub bundle_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Verdict printed by the submit command, if it waited for one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<String>,
+}
+
+impl Submission {
+    /// A submission made now, with the id and verdict taken from the submit
+    /// command's `output`.
+    pub(crate) fn new(judge: &str, bundle: &str, output: &str) -> Self {
+        Self {
+            timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            judge: judge.to_string(),
+            bundle_hash: bundle_hash(bundle),
+            id: submission_id(output),
+            verdict: verdict(output),
+        }
+    }
+}
+
+pub(crate) fn bundle_hash(bundle: &str) -> String {
+    format!("{:x}", Sha256::digest(bundle.as_bytes()))
+}
+
+fn submission_id(output: &str) -> Option<String> {
+    let patterns = [
+        r"/submissions?/(\d+)",
+        r"(?i)submission(?:\s+id)?\s*[:#=]?\s*(\d+)",
+    ];
+    patterns.into_iter().find_map(|pattern| {
+        Regex::new(pattern)
+            .unwrap()
+            .captures(output)
+            .map(|caps| caps[1].to_string())
+    })
+}
+
+const VERDICTS: [&str; 9] = [
+    "Accepted",
+    "Wrong answer",
+    "Time limit exceeded",
+    "Memory limit exceeded",
+    "Runtime error",
+    "Compilation error",
+    "Idleness limit exceeded",
+    "Presentation error",
+    "Partially accepted",
+];
+
+/// The last verdict mentioned in `output`.
+fn verdict(output: &str) -> Option<String> {
+    let line = Regex::new(r"(?im)^.*\bverdict\b\s*[:=]\s*(.+?)\s*$").unwrap();
+    if let Some(caps) = line.captures_iter(output).last() {
+        return Some(caps[1].to_string());
+    }
+    // Longer verdicts first, so "Partially accepted" isn't read as "Accepted".
+    let mut verdicts = VERDICTS.to_vec();
+    verdicts.sort_by_key(|verdict| std::cmp::Reverse(verdict.len()));
+    let any = Regex::new(&format!(r"(?i)\b({})\b", verdicts.join("|"))).unwrap();
+    let found = any.find_iter(output).last()?.as_str().to_lowercase();
+    VERDICTS
+        .iter()
+        .find(|verdict| verdict.to_lowercase() == found)
+        .map(|verdict| verdict.to_string())
+}
+
+pub(crate) fn load(task_dir: &str) -> Result<Vec<Submission>, String> {
+    let path = format!("{}/{}", task_dir, FILE);
+    if !Path::new(&path).exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|err| format!("Can't read {}: {}", path, err))?;
+    serde_json::from_str(&content).map_err(|err| format!("Can't parse {}: {}", path, err))
+}
+
+/// Appends `submission` to the task's history.
+pub(crate) fn record(task_dir: &str, submission: Submission) -> Result<(), String> {
+    let mut submissions = load(task_dir)?;
+    submissions.push(submission);
+    let path = format!("{}/{}", task_dir, FILE);
+    let content = serde_json::to_string_pretty(&submissions).map_err(|err| err.to_string())?;
+    std::fs::write(&path, content).map_err(|err| format!("Can't write {}: {}", path, err))
+}
+
+/// The directory of the task in `tasks/` that `task` was bundled from, found
+/// by its name and URL.
+pub(crate) fn task_dir_of(task: &Task) -> Option<String> {
+    let mut dirs = std::fs::read_dir("tasks")
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path().to_str()?.to_string()))
+        .collect::<Vec<_>>();
+    dirs.sort();
+    dirs.into_iter()
+        .find(|dir| load_task(dir).is_some_and(|other| runner::same_task(task, &other)))
+}
+
+fn print_submissions(submissions: &[Submission]) {
+    for submission in submissions {
+        println!(
+            "  {}  {:<12} {:<12} {:<10} {}",
+            submission.timestamp,
+            submission.judge,
+            submission.id.as_deref().unwrap_or("-"),
+            &submission.bundle_hash[..submission.bundle_hash.len().min(10)],
+            submission.verdict.as_deref().unwrap_or("-"),
+        );
+    }
+}
+
+/// Prints the submissions of `task_name`, or the latest submission of every
+/// task without a name.
+pub fn status(task_name: Option<&str>) -> Result<(), String> {
+    if let Some(task_name) = task_name {
+        let dir = format!("tasks/{}", task_name);
+        if load_task(&dir).is_none() {
+            return Err(format!("Task not found: {}", dir));
+        }
+        let submissions = load(&dir)?;
+        if submissions.is_empty() {
+            println!("{}: no submissions", task_name);
+        } else {
+            println!("{}:", task_name);
+            print_submissions(&submissions);
+        }
+        return Ok(());
+    }
+    let mut names = std::fs::read_dir("tasks")
+        .map_err(|err| format!("Can't read tasks: {}", err))?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect::<Vec<_>>();
+    names.sort();
+    for name in names {
+        let dir = format!("tasks/{}", name);
+        if load_task(&dir).is_none() {
+            continue;
+        }
+        let submissions = load(&dir)?;
+        match submissions.last() {
+            None => println!("{}: no submissions", name),
+            Some(last) => {
+                println!("{}: {} submission(s), latest:", name, submissions.len());
+                print_submissions(std::slice::from_ref(last));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{submission_id, verdict};
+
+    #[test]
+    fn test_parse_output() {
+        let output = "Submitted: https://codeforces.com/contest/1/submission/123456\n\
+                      Testing on test 5\nVerdict: Wrong answer on test 7\n";
+        assert_eq!(submission_id(output).as_deref(), Some("123456"));
+        assert_eq!(verdict(output).as_deref(), Some("Wrong answer on test 7"));
+        let output = "[SUCCESS] submission id: 987\nrunning...\nRuntime error\nAccepted\n";
+        assert_eq!(submission_id(output).as_deref(), Some("987"));
+        assert_eq!(verdict(output).as_deref(), Some("Accepted"));
+        assert_eq!(
+            verdict("Result: Partially accepted (40 points)").as_deref(),
+            Some("Partially accepted")
+        );
+        assert_eq!(verdict("Status: unaccepted").as_deref(), None);
+        assert_eq!(submission_id("done"), None);
+        assert_eq!(verdict("done"), None);
+    }
+}
@@ -0,0 +1,444 @@
+use crate::config::{Config, JudgeConfig};
+use crate::submit::{domain, site_of};
+use regex::Regex;
+
+const SUBMITTER_HINT: &str =
+    "If it is not installed please install submitter from https://github.com/EgorKulikov/submitter";
+const OJ_HINT: &str = "If it is not installed please install online judge tools from https://github.com/online-judge-tools/oj";
+const DMOJ_HINT: &str = "If it is not installed please install dmoj-submit from https://github.com/nils-emmenegger/dmoj-submit";
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+fn host_pattern(domain: &str) -> String {
+    format!(r"(^|\.){}$", regex::escape(domain))
+}
+
+fn submitter(name: &str, domain: &str) -> JudgeConfig {
+    JudgeConfig {
+        name: name.to_string(),
+        host: host_pattern(domain),
+        command: args(&["submitter", "$URL", "$LANG", "$FILE"]),
+        login: None,
+        language: Some("rust".to_string()),
+        problem_id: None,
+        install_hint: Some(SUBMITTER_HINT.to_string()),
+        source_limit: None,
+        fallback: Vec::new(),
+    }
+}
+
+fn oj(name: &str, domain: &str) -> JudgeConfig {
+    JudgeConfig {
+        name: name.to_string(),
+        host: host_pattern(domain),
+        command: args(&["oj", "submit", "$URL", "$FILE", "--yes", "--wait=0"]),
+        login: Some(args(&["oj", "login", "$URL"])),
+        language: None,
+        problem_id: None,
+        install_hint: Some(OJ_HINT.to_string()),
+        source_limit: None,
+        fallback: Vec::new(),
+    }
+}
+
+/// A config.toml judge submitting with `oj submit $URL`, for tests.
+#[cfg(test)]
+pub(crate) fn test_judge(name: &str, host: &str, fallback: &[&str]) -> JudgeConfig {
+    JudgeConfig {
+        name: name.to_string(),
+        host: host.to_string(),
+        command: args(&["oj", "submit", "$URL"]),
+        login: None,
+        language: None,
+        problem_id: None,
+        install_hint: None,
+        source_limit: None,
+        fallback: args(fallback),
+    }
+}
+
+/// Judges supported out of the box.
+pub(crate) fn builtin() -> Vec<JudgeConfig> {
+    let mut judges = [
+        ("codeforces", "codeforces.com"),
+        ("codechef", "codechef.com"),
+        ("ucup", "ucup.ac"),
+        ("eolymp", "eolymp.com"),
+        ("toph", "toph.co"),
+        ("yandex", "yandex.com"),
+        ("yandex", "yandex.ru"),
+        ("uoj", "uoj.ac"),
+        ("kattis", "kattis.com"),
+        ("atcoder", "atcoder.jp"),
+        ("luogu", "luogu.com.cn"),
+        ("kep", "kep.uz"),
+    ]
+    .into_iter()
+    .map(|(name, domain)| submitter(name, domain))
+    .collect::<Vec<_>>();
+    for (name, limit) in [
+        ("codeforces", 64 * 1024),
+        ("codechef", 50_000),
+        ("atcoder", 512 * 1024),
+    ] {
+        for judge in judges.iter_mut().filter(|judge| judge.name == name) {
+            judge.source_limit = Some(limit);
+        }
+    }
+    judges.push(oj("hackerrank", "hackerrank.com"));
+    judges.push(oj("yukicoder", "yukicoder.me"));
+    judges.push(JudgeConfig {
+        name: "dmoj".to_string(),
+        host: host_pattern("dmoj.ca"),
+        command: args(&[
+            "dmoj-submit",
+            "submit",
+            "--problem",
+            "$PROBLEM_ID",
+            "--language",
+            "$LANG",
+            "$FILE",
+        ]),
+        login: None,
+        language: Some("rust".to_string()),
+        problem_id: Some(r".*/problem/(?P<problem_id>\w+)([?].*)?".to_string()),
+        install_hint: Some(DMOJ_HINT.to_string()),
+        source_limit: Some(64 * 1024),
+        fallback: Vec::new(),
+    });
+    judges
+}
+
+fn host_matches(judge: &JudgeConfig, host: &str) -> Result<bool, String> {
+    let pattern = Regex::new(&judge.host)
+        .map_err(|err| format!("Invalid host pattern for judge {}: {}", judge.name, err))?;
+    Ok(pattern.is_match(host))
+}
+
+pub(crate) fn host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let host = host.split(':').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+/// The judge for `url`: the first configured judge whose host pattern
+/// matches, then the one `aliases` maps the host to, then the first built-in
+/// one matching the host or named after the site it's registered under. The
+/// `source_limit` of the URL's site, if set, replaces the judge's own.
+pub(crate) fn find(config: &Config, url: &str) -> Result<Option<JudgeConfig>, String> {
+    let Some(host) = host(url) else {
+        return Ok(None);
+    };
+    let mut judge = find_by_host(config, host)?;
+    if let Some(judge) = &mut judge {
+        if let Some(limit) = config.site(&site_of(config, url)).source_limit {
+            judge.source_limit = Some(limit);
+        }
+    }
+    Ok(judge)
+}
+
+fn find_by_host(config: &Config, host: &str) -> Result<Option<JudgeConfig>, String> {
+    for judge in &config.judges {
+        if host_matches(judge, host)? {
+            return Ok(Some(judge.clone()));
+        }
+    }
+    if let Some(judge) = domain::alias(config, host)?.and_then(|name| by_name(config, name)) {
+        return Ok(Some(judge));
+    }
+    let builtin = builtin();
+    for judge in &builtin {
+        if host_matches(judge, host)? {
+            return Ok(Some(judge.clone()));
+        }
+    }
+    let site = domain::registered_name(host);
+    Ok(builtin.into_iter().find(|judge| judge.name == site))
+}
+
+/// The judge named `name`, configured ones first.
+fn by_name(config: &Config, name: &str) -> Option<JudgeConfig> {
+    config
+        .judges
+        .iter()
+        .cloned()
+        .chain(builtin())
+        .find(|judge| judge.name == name)
+}
+
+/// `judge` followed by its fallbacks in the order they are tried. Each
+/// judge is tried once even if fallbacks refer to each other.
+pub(crate) fn chain(config: &Config, judge: JudgeConfig) -> Result<Vec<JudgeConfig>, String> {
+    let mut chain = vec![judge];
+    let mut next = 0;
+    while next < chain.len() {
+        for name in chain[next].fallback.clone() {
+            if chain.iter().any(|judge| judge.name == name) {
+                continue;
+            }
+            let fallback = by_name(config, &name).ok_or_else(|| {
+                format!("Unknown fallback judge {} for {}", name, chain[next].name)
+            })?;
+            chain.push(fallback);
+        }
+        next += 1;
+    }
+    Ok(chain)
+}
+
+/// `$PROBLEM_ID` for `url`, when the judge needs one.
+fn problem_id(judge: &JudgeConfig, url: &str) -> Result<Option<String>, String> {
+    let Some(pattern) = &judge.problem_id else {
+        return Ok(None);
+    };
+    let pattern = Regex::new(pattern).map_err(|err| {
+        format!(
+            "Invalid problem_id pattern for judge {}: {}",
+            judge.name, err
+        )
+    })?;
+    pattern
+        .captures(url)
+        .and_then(|caps| caps.name("problem_id"))
+        .map(|id| Some(id.as_str().to_string()))
+        .ok_or_else(|| format!("Unexpected URL for {} problem", judge.name))
+}
+
+/// Wraps URLs with characters outside the URL-safe set in double quotes.
+pub(crate) fn quote_url(url: &str) -> String {
+    if url.contains(|c: char| !c.is_ascii_alphanumeric() && !"-._~:/?[]@!$'+,;=%".contains(c)) {
+        format!("\"{}\"", url)
+    } else {
+        url.to_string()
+    }
+}
+
+/// Substitutes the template variables into `template`. The problem id is
+/// taken from the bare URL, `$URL` gets the quoted one.
+pub(crate) fn expand(
+    judge: &JudgeConfig,
+    template: &[String],
+    url: &str,
+    file: &str,
+) -> Result<Vec<String>, String> {
+    let problem_id = problem_id(judge, url)?;
+    let url = quote_url(url);
+    Ok(template
+        .iter()
+        .map(|arg| {
+            let mut arg = arg.replace("$URL", &url).replace("$FILE", file);
+            if let Some(language) = &judge.language {
+                arg = arg.replace("$LANG", language);
+            }
+            if let Some(problem_id) = &problem_id {
+                arg = arg.replace("$PROBLEM_ID", problem_id);
+            }
+            arg
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{builtin, chain, expand, find, host, quote_url, test_judge};
+    use crate::config::{AliasConfig, Config};
+
+    fn judge_name(config: &Config, url: &str) -> Option<String> {
+        find(config, url).unwrap().map(|judge| judge.name)
+    }
+
+    #[test]
+    fn test_host() {
+        assert_eq!(
+            host("https://www.luogu.com.cn/problem/P1"),
+            Some("www.luogu.com.cn")
+        );
+        assert_eq!(host("https://dmoj.ca"), Some("dmoj.ca"));
+        assert_eq!(host("http://localhost:8080/x"), Some("localhost"));
+        assert_eq!(host(""), None);
+    }
+
+    #[test]
+    fn test_quote_url() {
+        assert_eq!(
+            quote_url("https://codeforces.com/contest/1/problem/A"),
+            "https://codeforces.com/contest/1/problem/A"
+        );
+        assert_eq!(quote_url("https://x.com/a b"), "\"https://x.com/a b\"");
+    }
+
+    #[test]
+    fn test_every_builtin_judge() {
+        let config = Config::default();
+        let urls = [
+            ("https://codeforces.com/contest/1/problem/A", "codeforces"),
+            (
+                "https://m1.codeforces.com/contest/1/problem/A",
+                "codeforces",
+            ),
+            (
+                "https://mirror.codeforces.com/problemset/problem/1/A",
+                "codeforces",
+            ),
+            ("https://codeforces.ml/contest/1/problem/A", "codeforces"),
+            ("https://www.codechef.com/problems/TEST", "codechef"),
+            ("https://contest.ucup.ac/contest/1/problem/1", "ucup"),
+            ("https://eolymp.com/en/problems/1", "eolymp"),
+            ("https://toph.co/p/add-them-up", "toph"),
+            ("https://contest.yandex.com/contest/1/problems/A", "yandex"),
+            ("https://contest.yandex.ru/contest/1/problems/A", "yandex"),
+            ("https://uoj.ac/problem/1", "uoj"),
+            ("https://open.kattis.com/problems/hello", "kattis"),
+            (
+                "https://atcoder.jp/contests/abc300/tasks/abc300_a",
+                "atcoder",
+            ),
+            (
+                "https://abc300.contest.atcoder.jp/tasks/abc300_a",
+                "atcoder",
+            ),
+            ("https://www.luogu.com.cn/problem/P1001", "luogu"),
+            ("https://kep.uz/contests/487/problem/A", "kep"),
+            (
+                "https://www.hackerrank.com/challenges/x/problem",
+                "hackerrank",
+            ),
+            ("https://yukicoder.me/problems/no/1", "yukicoder"),
+            ("https://dmoj.ca/problem/helloworld", "dmoj"),
+        ];
+        for (url, name) in urls {
+            assert_eq!(judge_name(&config, url).as_deref(), Some(name), "{}", url);
+        }
+        for judge in builtin() {
+            assert!(
+                urls.iter().any(|(_, name)| *name == judge.name),
+                "no test URL for {}",
+                judge.name
+            );
+        }
+        assert_eq!(
+            judge_name(&config, "https://acm.timus.ru/problem.aspx"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_aliases() {
+        let mut config = Config::default();
+        config.aliases.push(AliasConfig {
+            host: r"(^|\.)codeforc\.es$".to_string(),
+            judge: "codeforces".to_string(),
+        });
+        config.aliases.push(AliasConfig {
+            host: r"^judge\.example$".to_string(),
+            judge: "cf-oj".to_string(),
+        });
+        config.judges.push(test_judge("cf-oj", "^$", &[]));
+        assert_eq!(
+            judge_name(&config, "https://codeforc.es/contest/1/problem/A").as_deref(),
+            Some("codeforces")
+        );
+        assert_eq!(
+            judge_name(&config, "https://judge.example/x").as_deref(),
+            Some("cf-oj")
+        );
+        config.aliases.push(AliasConfig {
+            host: r"^acm\.timus\.ru$".to_string(),
+            judge: "timus".to_string(),
+        });
+        assert_eq!(
+            judge_name(&config, "https://acm.timus.ru/problem.aspx"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_config_overrides_builtin() {
+        let mut config = Config::default();
+        config
+            .judges
+            .push(test_judge("cf-oj", r"codeforces\.com$", &[]));
+        assert_eq!(
+            judge_name(&config, "https://codeforces.com/contest/1/problem/A").as_deref(),
+            Some("cf-oj")
+        );
+    }
+
+    #[test]
+    fn test_site_source_limit() {
+        let mut config = Config::default();
+        let url = "https://codeforces.com/contest/1/problem/A";
+        let limit = |config: &Config| find(config, url).unwrap().unwrap().source_limit;
+        assert_eq!(limit(&config), Some(64 * 1024));
+        config
+            .sites
+            .entry("codeforces".to_string())
+            .or_default()
+            .source_limit = Some(1000);
+        assert_eq!(limit(&config), Some(1000));
+        assert_eq!(
+            find(&config, "https://atcoder.jp/contests/abc300/tasks/abc300_a")
+                .unwrap()
+                .unwrap()
+                .source_limit,
+            Some(512 * 1024)
+        );
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        let mut config = Config::default();
+        config
+            .judges
+            .push(test_judge("cf-oj", "^$", &["codeforces"]));
+        let mut codeforces = find(&config, "https://codeforces.com/contest/1/problem/A")
+            .unwrap()
+            .unwrap();
+        codeforces.fallback = vec!["cf-oj".to_string(), "atcoder".to_string()];
+        let names = chain(&config, codeforces.clone())
+            .unwrap()
+            .into_iter()
+            .map(|judge| judge.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["codeforces", "cf-oj", "atcoder"]);
+        codeforces.fallback = vec!["nowhere".to_string()];
+        assert!(chain(&config, codeforces).is_err());
+    }
+
+    #[test]
+    fn test_expand() {
+        let judge = find(&Config::default(), "https://dmoj.ca/problem/helloworld")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            expand(
+                &judge,
+                &judge.command,
+                "https://dmoj.ca/problem/helloworld",
+                "main.rs"
+            )
+            .unwrap(),
+            vec![
+                "dmoj-submit",
+                "submit",
+                "--problem",
+                "helloworld",
+                "--language",
+                "rust",
+                "main.rs"
+            ]
+        );
+        assert!(expand(
+            &judge,
+            &judge.command,
+            "https://dmoj.ca/contests",
+            "main.rs"
+        )
+        .is_err());
+    }
+}
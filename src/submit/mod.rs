@@ -1,12 +1,28 @@
-mod dmoj;
-mod oj;
-mod submitter;
+mod bundle;
+mod clipboard;
+mod command;
+mod contest;
+mod domain;
+mod dry_run;
+mod history;
+mod judge;
+mod queue;
+mod size;
 
-use clipboard::{ClipboardContext, ClipboardProvider};
+pub use history::status;
+pub use queue::{pending, print_retry_notices, retry_now, start_background_retry};
+
+use crate::config::{Config, JudgeConfig};
+use crate::runner;
+use clipboard::Copied;
+use command::SubmitError;
 use crossterm::execute;
 use crossterm::style::{Color, ResetColor, SetForegroundColor};
-use regex::Regex;
+use history::Submission;
 use rust_competitive_helper_util::{load_task, read_from_file, read_lines};
+use std::time::Duration;
+
+const BUNDLE: &str = "main/src/main.rs";
 
 fn read_main_url() -> Option<String> {
     if let Some(task) = load_task("main") {
@@ -16,58 +32,280 @@ fn read_main_url() -> Option<String> {
     Some(first.strip_prefix("//")?.trim().to_string())
 }
 
-fn extract_site(url: &str) -> String {
-    let url_regex = Regex::new(r"https?://(?:www\.)?([^/]+)").unwrap();
-    match url_regex.captures(url) {
-        None => String::new(),
-        Some(caps) => {
-            let host = &caps[1];
-            let parts: Vec<&str> = host.split('.').collect();
-            // Skip TLD parts from the end to find the second-level domain
-            // e.g. "codeforces.com" -> "codeforces", "contest.yandex.com" -> "yandex"
-            //      "luogu.com.cn" -> "luogu"
-            let tlds = [
-                "com", "org", "net", "co", "ac", "me", "ca", "cn", "ru", "jp", "uz",
-            ];
-            let sld = parts
-                .iter()
-                .rev()
-                .find(|p| !tlds.contains(p))
-                .unwrap_or(&"");
-            sld.to_string()
+pub(crate) fn extract_site(url: &str) -> String {
+    if !url.contains("://") {
+        return String::new();
+    }
+    judge::host(url)
+        .map(domain::registered_name)
+        .unwrap_or_default()
+}
+
+/// The site `url` belongs to: the judge `aliases` maps its host to, or else
+/// the name the host is registered under. Invalid alias patterns are
+/// skipped here and reported when a judge is looked up.
+pub(crate) fn site_of(config: &Config, url: &str) -> String {
+    judge::host(url)
+        .and_then(|host| domain::alias(config, host).ok().flatten())
+        .map_or_else(|| extract_site(url), str::to_string)
+}
+
+#[derive(Default)]
+pub struct SubmitOptions {
+    /// Task to rebuild the bundle for; without it `main/` is submitted as is.
+    pub task: Option<String>,
+    /// Verify the bundle even if `verify_before_submit` is off in config.
+    pub verify: bool,
+    /// Submit even if verification fails.
+    pub force: bool,
+    /// Only print how the submission would be made.
+    pub dry_run: bool,
+    /// Submit every task of this contest instead of `main/`.
+    pub contest: Option<String>,
+    /// Seconds between contest submissions, overriding `submit_delay`.
+    pub delay: Option<u64>,
+    /// Resend queued submissions instead.
+    pub retry: bool,
+}
+
+/// Checks the bundle against the source size limit of the judge for `url`.
+/// Returns `false` if the submission should be aborted.
+fn fits(config: &Config, url: &str, task_dir: Option<&str>) -> bool {
+    let judge = match judge::find(config, url) {
+        Ok(Some(judge)) => judge,
+        Ok(None) => return true,
+        Err(err) => {
+            failure(&err);
+            return false;
+        }
+    };
+    let task_name = task_dir.map(|dir| dir.trim_start_matches("tasks/"));
+    match size::check(&judge, task_name) {
+        Ok(true) => true,
+        Ok(false) => {
+            failure("Bundle is over the source size limit, not submitting");
+            false
+        }
+        Err(err) => {
+            failure(&err);
+            false
         }
     }
 }
 
-pub fn submit() {
-    let url = read_main_url().unwrap_or_default();
-    let site = extract_site(&url);
-    let quoted_url = if url
-        .contains(|c: char| !c.is_ascii_alphanumeric() && !"-._~:/?[]@!$'+,;=%".contains(c))
-    {
-        format!("\"{}\"", url)
+/// Runs bundle verification when requested. Returns `false` if the
+/// submission should be aborted.
+fn verified(config: &Config, options: &SubmitOptions) -> bool {
+    if !options.verify && !config.verify_before_submit {
+        return true;
+    }
+    let passed = runner::verify().unwrap_or_else(|err| {
+        failure(&err);
+        false
+    });
+    if passed {
+        return true;
+    }
+    if options.force {
+        failure("Verification failed, submitting anyway");
+        true
     } else {
-        url
+        failure("Verification failed, not submitting (use --force to override)");
+        false
+    }
+}
+
+pub fn submit(options: &SubmitOptions) {
+    let config = Config::load();
+    if options.dry_run {
+        if let Err(err) = dry_run::explain(&config, options) {
+            failure(&err);
+        }
+        return;
+    }
+    if options.retry {
+        retry_now();
+        return;
+    }
+    if let Some(contest) = &options.contest {
+        let delay = options.delay.unwrap_or(config.submit_delay);
+        contest::submit_contest(&config, contest, Duration::from_secs(delay), options.force);
+        return;
+    }
+    let task_dir = match &options.task {
+        Some(task_name) => match bundle::prepare(task_name) {
+            Ok(dir) => Some(dir),
+            Err(err) => {
+                failure(&err);
+                return;
+            }
+        },
+        None => bundle::warn_if_stale(),
     };
-    let submitted = match site.as_str() {
-        "codeforces" | "codechef" | "ucup" | "eolymp" | "toph" | "yandex" | "uoj" | "kattis"
-        | "atcoder" | "luogu" | "kep" => submitter::submit(&quoted_url),
-        "hackerrank" | "yukicoder" => oj::submit(&quoted_url),
-        "dmoj" => dmoj::submit(&quoted_url),
-        _ => false,
+    let url = read_main_url().unwrap_or_default();
+    if !fits(&config, &url, task_dir.as_deref()) {
+        return;
+    }
+    if !verified(&config, options) {
+        return;
+    }
+    let site = site_of(&config, &url);
+    let bundle = read_from_file(BUNDLE).unwrap_or_default();
+    let request = Request {
+        url: &url,
+        file: BUNDLE,
+        language: None,
+        quiet: false,
     };
-    if !submitted {
-        println!(
-            "Unsupported site or error submitting, code copied to clipboard: {}",
-            site
-        );
-        let mut ctx: ClipboardContext = ClipboardProvider::new().unwrap();
-        ctx.set_contents(read_from_file("main/src/main.rs").unwrap())
-            .unwrap();
+    let sent = submit_with_fallbacks(&config, &request).unwrap_or_else(|err| {
+        failure(&err);
+        Sent::Unsupported
+    });
+    match sent {
+        Sent::Submitted { judge, output } => {
+            record_submission(task_dir, Submission::new(&judge, &bundle, &output));
+            return;
+        }
+        Sent::Failed {
+            judge,
+            transient: true,
+            reason,
+        } => {
+            if queue::enqueue(&judge, &url, task_dir, &bundle, &reason) {
+                return;
+            }
+        }
+        Sent::Failed { .. } | Sent::Unsupported => {}
+    }
+    let place = match clipboard::copy(&bundle) {
+        Ok(Copied::Clipboard) => "code copied to clipboard".to_string(),
+        Ok(Copied::Terminal) => format!(
+            "code copied to clipboard through the terminal (OSC 52), if it didn't arrive \
+             copy it from {}",
+            BUNDLE
+        ),
+        Ok(Copied::File(path)) => {
+            format!("no clipboard available, code written to {}", path.display())
+        }
+        Err(err) => format!("{}, the code is in {}", err, BUNDLE),
+    };
+    println!("Unsupported site or error submitting, {}: {}", place, site);
+}
+
+/// Where a bundle goes and how, for submitting it or a queued snapshot.
+pub(crate) struct Request<'a> {
+    pub url: &'a str,
+    /// Path passed as `$FILE`.
+    pub file: &'a str,
+    /// Replaces the language of the judge for the URL, as it was when a
+    /// queued submission was made.
+    pub language: Option<&'a str>,
+    /// Don't show the commands' output or why judges failed.
+    pub quiet: bool,
+}
+
+/// How sending a bundle to the judges went.
+pub(crate) enum Sent {
+    Submitted {
+        judge: String,
+        output: String,
+    },
+    /// No judge handles the URL.
+    Unsupported,
+    /// Every judge failed. `judge` is the one for the URL.
+    Failed {
+        judge: JudgeConfig,
+        reason: String,
+        /// Some judge failed in a way that may go away on a retry.
+        transient: bool,
+    },
+}
+
+/// Submits the bundle with the judge's command, logging in first when the
+/// judge needs it.
+fn run_judge(judge: &JudgeConfig, request: &Request) -> Result<String, SubmitError> {
+    let hint = judge.install_hint.as_deref();
+    let expand = |template: &[String]| {
+        judge::expand(judge, template, request.url, request.file).map_err(SubmitError::Failed)
+    };
+    if let Some(login) = &judge.login {
+        command::run(&expand(login)?, hint, request.quiet)?;
+    }
+    command::run(&expand(&judge.command)?, hint, request.quiet)
+}
+
+/// Tries the judge for the URL and then its fallbacks, reporting why each
+/// one failed.
+fn submit_with_fallbacks(config: &Config, request: &Request) -> Result<Sent, String> {
+    let Some(mut judge) = judge::find(config, request.url)? else {
+        return Ok(Sent::Unsupported);
+    };
+    if let Some(language) = request.language {
+        judge.language = Some(language.to_string());
+    }
+    let mut reasons = Vec::new();
+    let mut transient = false;
+    for fallback in judge::chain(config, judge.clone())? {
+        match run_judge(&fallback, request) {
+            Ok(output) => {
+                return Ok(Sent::Submitted {
+                    judge: fallback.name,
+                    output,
+                })
+            }
+            Err(err) => {
+                if !request.quiet {
+                    failure(&format!("Submitting with {}: {}", fallback.name, err));
+                }
+                transient |= err.is_transient();
+                reasons.push(format!("{}: {}", fallback.name, err));
+            }
+        }
     }
+    Ok(Sent::Failed {
+        judge,
+        reason: reasons.join("; "),
+        transient,
+    })
+}
+
+/// Adds the submission to the history of the task the bundle was built
+/// from. Returns a summary of what was recorded.
+fn save_submission(
+    task_dir: Option<String>,
+    submission: Submission,
+) -> Result<Option<String>, String> {
+    let Some(dir) = task_dir else {
+        return Ok(None);
+    };
+    let summary = format!(
+        "Recorded submission{}{} in {}",
+        submission
+            .id
+            .as_ref()
+            .map(|id| format!(" {}", id))
+            .unwrap_or_default(),
+        submission
+            .verdict
+            .as_ref()
+            .map(|verdict| format!(" ({})", verdict))
+            .unwrap_or_default(),
+        dir
+    );
+    history::record(&dir, submission)?;
+    Ok(Some(summary))
 }
 
-fn failure(s: &str) {
+/// Like [`save_submission`], printing the summary.
+fn record_submission(task_dir: Option<String>, submission: Submission) {
+    match save_submission(task_dir, submission) {
+        Ok(Some(summary)) => println!("{}", summary),
+        Ok(None) => {}
+        Err(err) => failure(&err),
+    }
+}
+
+pub(crate) fn failure(s: &str) {
     let mut stdout = std::io::stdout();
     let _ = execute!(stdout, SetForegroundColor(Color::Red));
     println!("{s}");
@@ -76,7 +314,8 @@ fn failure(s: &str) {
 
 #[cfg(test)]
 mod tests {
-    use super::extract_site;
+    use super::{extract_site, site_of};
+    use crate::config::{AliasConfig, Config};
 
     #[test]
     fn test_extract_site() {
@@ -98,10 +337,35 @@ mod tests {
         assert_eq!(extract_site("https://yukicoder.me/foo"), "yukicoder");
         assert_eq!(extract_site("https://www.luogu.com.cn/foo"), "luogu");
         assert_eq!(extract_site("https://uoj.ac/foo"), "uoj");
+        assert_eq!(extract_site("https://kep.uz/contests/487/problem/A"), "kep");
+        assert_eq!(
+            extract_site("https://m1.codeforces.com/contest/1"),
+            "codeforces"
+        );
+        assert_eq!(
+            extract_site("https://codeforces.ml/contest/1"),
+            "codeforces"
+        );
+        assert_eq!(extract_site("https://acm.timus.ru/problem.aspx"), "timus");
+        assert_eq!(extract_site("http://localhost:8080/x"), "localhost");
+    }
+
+    #[test]
+    fn test_site_of() {
+        let mut config = Config::default();
+        config.aliases.push(AliasConfig {
+            host: r"(^|\.)codeforc\.es$".to_string(),
+            judge: "codeforces".to_string(),
+        });
+        assert_eq!(
+            site_of(&config, "https://codeforc.es/contest/1"),
+            "codeforces"
+        );
         assert_eq!(
-            extract_site("https://kep.uz/contests/487/problem/A"),
-            "kep"
+            site_of(&config, "https://atcoder.jp/contests/abc300"),
+            "atcoder"
         );
+        assert_eq!(site_of(&config, "not a url"), "");
     }
 
     #[test]
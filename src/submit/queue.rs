@@ -0,0 +1,352 @@
+use crate::config::{Config, JudgeConfig};
+use crate::runner::ScratchDir;
+use crate::submit::history::Submission;
+use crate::submit::{failure, save_submission, submit_with_fallbacks, Request, Sent};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const FILE: &str = "submit_queue.json";
+
+/// Guards read-modify-write cycles of the queue file, which the menu's
+/// background retries share with submissions made in the foreground.
+static LOCK: Mutex<()> = Mutex::new(());
+static RETRYING: AtomicBool = AtomicBool::new(false);
+/// Lines reported by background retries, each with whether it's a failure.
+static NOTICES: Mutex<Vec<(String, bool)>> = Mutex::new(Vec::new());
+
+/// A submission that failed because the judge couldn't be reached, kept
+/// with a snapshot of the bundle to be sent again later. Entries that then
+/// fail for another reason stay with `failed` set.
+#[derive(Serialize, Deserialize, Clone)]
+struct Queued {
+    /// Unique within the queue.
+    id: u128,
+    queued_at: String,
+    url: String,
+    judge: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    /// Task directory the submission is recorded in once it goes through.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    task_dir: Option<String>,
+    bundle: String,
+    last_error: String,
+    #[serde(default)]
+    attempts: u32,
+    /// The last attempt failed for a reason other than the network, so only
+    /// `submit --retry` sends it again.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    failed: bool,
+}
+
+fn load(path: &Path) -> Result<Vec<Queued>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| format!("Can't read {}: {}", path.display(), err))?;
+    serde_json::from_str(&content).map_err(|err| format!("Can't parse {}: {}", path.display(), err))
+}
+
+/// Writes the queue, removing the file once it's empty.
+fn save(path: &Path, queue: &[Queued]) -> Result<(), String> {
+    if queue.is_empty() {
+        return match std::fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Can't remove {}: {}", path.display(), err))
+            }
+            _ => Ok(()),
+        };
+    }
+    let content = serde_json::to_string_pretty(queue).map_err(|err| err.to_string())?;
+    std::fs::write(path, content).map_err(|err| format!("Can't write {}: {}", path.display(), err))
+}
+
+/// Reads the queue file under the lock.
+fn read<T>(view: impl FnOnce(&[Queued]) -> T) -> Result<T, String> {
+    let _guard = LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    Ok(view(&load(Path::new(FILE))?))
+}
+
+/// Applies `change` to the queue file under the lock.
+fn update<T>(change: impl FnOnce(&mut Vec<Queued>) -> T) -> Result<T, String> {
+    let _guard = LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    let path = Path::new(FILE);
+    let mut queue = load(path)?;
+    let res = change(&mut queue);
+    save(path, &queue)?;
+    Ok(res)
+}
+
+/// Number of submissions waiting in the queue.
+pub fn pending() -> Result<usize, String> {
+    read(|queue| queue.len())
+}
+
+/// Prints what background retries reported since the last call. They don't
+/// print themselves so as not to draw over the menu.
+pub fn print_retry_notices() {
+    let notices = std::mem::take(&mut *NOTICES.lock().unwrap_or_else(|err| err.into_inner()));
+    for (line, failed) in notices {
+        if failed {
+            failure(&line);
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Queues a submission the judge couldn't be reached for. Returns `false`
+/// if it couldn't be stored.
+pub(crate) fn enqueue(
+    judge: &JudgeConfig,
+    url: &str,
+    task_dir: Option<String>,
+    bundle: &str,
+    reason: &str,
+) -> bool {
+    let id = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_nanos());
+    let entry = Queued {
+        id,
+        queued_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        url: url.to_string(),
+        judge: judge.name.clone(),
+        language: judge.language.clone(),
+        task_dir,
+        bundle: bundle.to_string(),
+        last_error: reason.to_string(),
+        attempts: 1,
+        failed: false,
+    };
+    match update(|queue| {
+        queue.push(entry);
+        queue.len()
+    }) {
+        Ok(len) => {
+            failure(&format!(
+                "Judge unreachable, submission queued ({} in {}). \
+                 Run `submit --retry` or let the menu retry it.",
+                len, FILE
+            ));
+            true
+        }
+        Err(err) => {
+            failure(&err);
+            false
+        }
+    }
+}
+
+/// Sends one queued submission.
+fn send(config: &Config, entry: &Queued, quiet: bool) -> Result<Sent, String> {
+    let dir = ScratchDir::new("retry")?;
+    let file = dir.0.join("main.rs");
+    std::fs::write(&file, &entry.bundle)
+        .map_err(|err| format!("Can't write {}: {}", file.display(), err))?;
+    let file = file.to_string_lossy();
+    let request = Request {
+        url: &entry.url,
+        file: &file,
+        language: entry.language.as_deref(),
+        quiet,
+    };
+    submit_with_fallbacks(config, &request)
+}
+
+/// Resends queued submissions oldest first, stopping at the first one the
+/// judge still can't be reached for so they arrive in order. Submissions
+/// failing for another reason are marked as failed and kept with their
+/// bundle, and are only sent again when `include_failed`. Progress goes to
+/// `report` with whether it's a failure. Returns how many were submitted and
+/// how many are left.
+fn retry(
+    config: &Config,
+    quiet: bool,
+    include_failed: bool,
+    report: &mut dyn FnMut(String, bool),
+) -> Result<(usize, usize), String> {
+    if RETRYING.swap(true, Ordering::SeqCst) {
+        return Err("Queued submissions are already being retried".to_string());
+    }
+    let res = retry_all(config, quiet, include_failed, report);
+    RETRYING.store(false, Ordering::SeqCst);
+    res
+}
+
+fn retry_all(
+    config: &Config,
+    quiet: bool,
+    include_failed: bool,
+    report: &mut dyn FnMut(String, bool),
+) -> Result<(usize, usize), String> {
+    let ids = read(|queue| {
+        queue
+            .iter()
+            .filter(|entry| include_failed || !entry.failed)
+            .map(|entry| entry.id)
+            .collect::<Vec<_>>()
+    })?;
+    let mut submitted = 0;
+    for id in ids {
+        let Some(entry) = read(|queue| queue.iter().find(|entry| entry.id == id).cloned())? else {
+            continue;
+        };
+        if !quiet {
+            println!("Retrying {} queued at {}", entry.url, entry.queued_at);
+        }
+        let (reason, transient) = match send(config, &entry, quiet)? {
+            Sent::Submitted { judge, output } => {
+                update(|queue| queue.retain(|other| other.id != entry.id))?;
+                report(
+                    format!("Submitted queued {} with {}", entry.url, judge),
+                    false,
+                );
+                let submission = Submission::new(&judge, &entry.bundle, &output);
+                match save_submission(entry.task_dir.clone(), submission) {
+                    Ok(Some(summary)) => report(summary, false),
+                    Ok(None) => {}
+                    Err(err) => report(err, true),
+                }
+                submitted += 1;
+                continue;
+            }
+            Sent::Unsupported => ("no judge handles the URL anymore".to_string(), false),
+            Sent::Failed {
+                reason, transient, ..
+            } => (reason, transient),
+        };
+        let left = update(|queue| {
+            if let Some(other) = queue.iter_mut().find(|other| other.id == entry.id) {
+                other.attempts += 1;
+                other.last_error = reason.clone();
+                other.failed = !transient;
+            }
+            queue.len()
+        })?;
+        if transient {
+            return Ok((submitted, left));
+        }
+        report(
+            format!(
+                "Queued {} from {} failed and is kept in {}, fix it and run `submit --retry`: {}",
+                entry.url, entry.queued_at, FILE, reason
+            ),
+            true,
+        );
+    }
+    Ok((submitted, pending()?))
+}
+
+/// Prints the queue and retries it, for `submit --retry` and the menu.
+pub fn retry_now() {
+    let config = Config::load();
+    let queue = match read(|queue| queue.to_vec()) {
+        Ok(queue) => queue,
+        Err(err) => {
+            failure(&err);
+            return;
+        }
+    };
+    if queue.is_empty() {
+        println!("No queued submissions");
+        return;
+    }
+    println!("Queued submissions:");
+    for entry in &queue {
+        println!(
+            "  {}  {:<12} {}  ({} attempt(s), {}: {})",
+            entry.queued_at,
+            entry.judge,
+            entry.url,
+            entry.attempts,
+            if entry.failed { "failed" } else { "last error" },
+            entry.last_error
+        );
+    }
+    let mut report = |line: String, failed: bool| {
+        if failed {
+            failure(&line);
+        } else {
+            println!("{}", line);
+        }
+    };
+    match retry(&config, false, true, &mut report) {
+        Ok((submitted, 0)) => println!("Submitted {}, queue is empty", submitted),
+        Ok((submitted, left)) => {
+            failure(&format!("Submitted {}, {} still queued", submitted, left))
+        }
+        Err(err) => failure(&err),
+    }
+}
+
+/// Retries queued submissions every `retry_interval` seconds while the
+/// menu runs. What happened is kept for [`print_retry_notices`].
+pub fn start_background_retry() {
+    let interval = Config::load().retry_interval;
+    if interval == 0 {
+        return;
+    }
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(interval));
+        if pending().unwrap_or(0) == 0 {
+            continue;
+        }
+        let config = Config::load();
+        let mut report = |line: String, failed: bool| {
+            NOTICES
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .push((line, failed));
+        };
+        let _ = retry(&config, true, false, &mut report);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load, save, Queued};
+    use crate::runner::ScratchDir;
+
+    fn entry(id: u128) -> Queued {
+        Queued {
+            id,
+            queued_at: "2026-10-19 12:00:00".to_string(),
+            url: format!("https://codeforces.com/contest/1/problem/{}", id),
+            judge: "codeforces".to_string(),
+            language: Some("rust".to_string()),
+            task_dir: None,
+            bundle: "fn main() {}".to_string(),
+            last_error: "network error".to_string(),
+            attempts: 1,
+            failed: false,
+        }
+    }
+
+    #[test]
+    fn test_queue_file() {
+        let dir = ScratchDir::new("queue").unwrap();
+        let path = dir.0.join("submit_queue.json");
+        assert!(load(&path).unwrap().is_empty());
+        save(&path, &[entry(1), entry(2)]).unwrap();
+        let queue = load(&path).unwrap();
+        assert_eq!(
+            queue.iter().map(|entry| entry.id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(queue[0].language.as_deref(), Some("rust"));
+        assert!(!queue[0].failed);
+        let mut failed = entry(3);
+        failed.failed = true;
+        save(&path, &[failed]).unwrap();
+        assert!(load(&path).unwrap()[0].failed);
+        save(&path, &[]).unwrap();
+        assert!(!path.exists());
+        save(&path, &[]).unwrap();
+    }
+}
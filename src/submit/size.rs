@@ -0,0 +1,152 @@
+use crate::config::JudgeConfig;
+use crate::submit::{failure, BUNDLE};
+use rust_competitive_helper_util::build::{MINIMIZE_ENV, TASK_ENV};
+use std::process::Command;
+use syn::spanned::Spanned;
+use syn::Item;
+
+/// How many modules to list when the bundle is too large.
+const REPORTED_MODULES: usize = 10;
+
+pub(crate) fn kb(bytes: u64) -> String {
+    format!("{:.1} KB", bytes as f64 / 1024.0)
+}
+
+fn bundle_size() -> Result<u64, String> {
+    std::fs::metadata(BUNDLE)
+        .map(|metadata| metadata.len())
+        .map_err(|err| format!("Can't read {}: {}", BUNDLE, err))
+}
+
+/// Regenerates the bundle of `task_name` with library code minimized.
+fn rebuild_minimized(task_name: &str) -> Result<(), String> {
+    let status = Command::new("cargo")
+        .args(["build", "--release", "--package", task_name])
+        .env(MINIMIZE_ENV, "1")
+        .env(TASK_ENV, task_name)
+        .status()
+        .map_err(|err| format!("Couldn't run cargo: {}", err))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Minimized build of {} failed", task_name))
+    }
+}
+
+/// Adds the size of every module among `items` to `sizes`, without its
+/// child modules, and returns the total size of those modules.
+fn add_modules(items: &[Item], parent: &str, sizes: &mut Vec<(String, u64)>) -> u64 {
+    let mut total = 0;
+    for item in items {
+        let Item::Mod(module) = item else {
+            continue;
+        };
+        let path = match parent {
+            "" => module.ident.to_string(),
+            parent => format!("{}::{}", parent, module.ident),
+        };
+        let range = module.span().byte_range();
+        let size = range.len() as u64;
+        let at = sizes.len();
+        sizes.push((path.clone(), size));
+        if let Some((_, items)) = &module.content {
+            sizes[at].1 -= add_modules(items, &path, sizes);
+        }
+        total += size;
+    }
+    total
+}
+
+/// Bytes each module of the bundle contributes by itself, without its
+/// child modules, keyed by module path, largest first.
+fn module_sizes(code: &str) -> Result<Vec<(String, u64)>, String> {
+    let file = syn::parse_file(code).map_err(|err| format!("Can't parse {}: {}", BUNDLE, err))?;
+    let mut sizes = Vec::new();
+    let modules = add_modules(&file.items, "", &mut sizes);
+    sizes.push(("(top level)".to_string(), code.len() as u64 - modules));
+    sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(sizes)
+}
+
+fn report_modules() {
+    let Ok(code) = std::fs::read_to_string(BUNDLE) else {
+        return;
+    };
+    let sizes = match module_sizes(&code) {
+        Ok(sizes) => sizes,
+        Err(err) => {
+            failure(&err);
+            return;
+        }
+    };
+    println!("Largest modules:");
+    for (path, size) in sizes.into_iter().take(REPORTED_MODULES) {
+        println!("  {:>9}  {}", kb(size), path);
+    }
+}
+
+/// Checks the bundle against the judge's source size limit, rebuilding it
+/// minimized when it's too large. Returns whether the bundle fits.
+pub(crate) fn check(judge: &JudgeConfig, task_name: Option<&str>) -> Result<bool, String> {
+    let Some(limit) = judge.source_limit else {
+        return Ok(true);
+    };
+    let size = bundle_size()?;
+    if size <= limit {
+        return Ok(true);
+    }
+    println!(
+        "{} is {}, over the {} limit of {}",
+        BUNDLE,
+        kb(size),
+        kb(limit),
+        judge.name
+    );
+    let Some(task_name) = task_name else {
+        failure("Can't tell which task the bundle is for, not minimizing it");
+        report_modules();
+        return Ok(false);
+    };
+    println!("Rebuilding {} minimized", task_name);
+    rebuild_minimized(task_name)?;
+    let size = bundle_size()?;
+    if size <= limit {
+        println!("Minimized to {}", kb(size));
+        return Ok(true);
+    }
+    failure(&format!(
+        "Minimized bundle is still {}, over the {} limit",
+        kb(size),
+        kb(limit)
+    ));
+    report_modules();
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::module_sizes;
+
+    #[test]
+    fn test_module_sizes() {
+        let code = "fn main() {}\n\
+                    pub mod algo_lib {\n\
+                    pub mod io { fn f() -> &'static str { \"} mod x {\" } }\n\
+                    pub mod misc { const C: char = '}'; /* mod y { */ }\n\
+                    }\n";
+        let sizes = module_sizes(code).unwrap();
+        let size = |path: &str| sizes.iter().find(|(p, _)| p == path).map(|(_, size)| *size);
+        assert_eq!(
+            sizes.iter().map(|(_, size)| size).sum::<u64>(),
+            code.len() as u64
+        );
+        assert_eq!(size("algo_lib::io"), Some(53));
+        assert_eq!(size("algo_lib::misc"), Some(51));
+        assert_eq!(size("algo_lib"), Some(22));
+        assert_eq!(size("(top level)"), Some(14));
+        assert_eq!(size("algo_lib::io::x"), None);
+        assert_eq!(size("algo_lib::misc::y"), None);
+        assert_eq!(sizes[0].0, "algo_lib::io");
+        assert!(module_sizes("pub mod broken {").is_err());
+    }
+}
@@ -4,7 +4,8 @@ use dialoguer::theme::ColorfulTheme;
 use dialoguer::{Input, Select};
 use rand::random;
 use rust_competitive_helper_util::{
-    read_from_file, read_lines, write_lines, write_to_file, IOEnum, IOType, Task, Test, TestType,
+    read_from_file, read_lines, write_lines, write_to_file, IOEnum, IOType, Task, Test, TestSource,
+    TestType,
 };
 use std::collections::HashMap;
 use std::fs;
@@ -361,13 +362,9 @@ pub fn create_task_wizard() {
         url: "".to_string(),
         interactive: false,
         time_limit: 2000,
-        tests: vec![
-            Test {
-                input: "".to_string(),
-                output: "".to_string()
-            };
-            select_num_tests()
-        ],
+        memory_limit: None,
+        tests: vec![Test::new(TestSource::Manual); select_num_tests()],
+        groups: Vec::new(),
         test_type: select_test_type(),
         input: select_input_type(),
         output: select_output_type(),
@@ -387,7 +384,9 @@ mod tests {
             url: String::new(),
             interactive: false,
             time_limit: 2000,
+            memory_limit: None,
             tests: vec![],
+            groups: vec![],
             test_type: TestType::Single,
             input: IOType {
                 io_type: IOEnum::StdIn,